@@ -1,39 +1,76 @@
 extern crate workspace_opener;
-use crossterm::{
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+use clap::Parser;
+use std::{error::Error, io};
+use workspace_opener::workspace_opener_lib::{
+    cli::{self, Cli},
+    logging::{self, LogOptions},
 };
-use log::*;
-use simplelog::{Config, WriteLogger};
-use std::{error::Error, fs::File, io};
-use tui::{backend::CrosstermBackend, Terminal};
-use workspace_opener::workspace_opener_lib::{api::run_app, model::App};
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let _ = WriteLogger::init(
-        LevelFilter::Info,
-        Config::default(),
-        File::create("output.log").unwrap(),
-    );
-    enable_raw_mode()?;
+    let mut cli = Cli::parse();
 
-    let mut stdout = io::stdout();
+    let log_options = cli.global.log_options();
+    if let Err(err) = logging::init(&log_options) {
+        eprintln!("warning: logging is disabled. {}", err);
+    }
 
-    execute!(stdout, EnterAlternateScreen)?;
+    if let Some(command) = cli.command.take() {
+        if let Err(err) = cli::execute(command, &cli.global, &mut io::stdout()) {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
-    let backend = CrosstermBackend::new(stdout);
+    run_tui(&cli, &log_options)
+}
 
-    let mut terminal = Terminal::new(backend).unwrap();
+#[cfg(feature = "tui")]
+fn run_tui(cli: &Cli, log_options: &LogOptions) -> Result<(), Box<dyn Error>> {
+    use std::{path::PathBuf, sync::Arc};
+    use tui::{backend::CrosstermBackend, Terminal};
+    use workspace_opener::workspace_opener_lib::{
+        api::run_app,
+        app::App,
+        crash::{self, TerminalGuard},
+        events::{self, EventSource, Events},
+        model::{AppConfig, LaunchPlan},
+    };
 
-    let mut app = App::default();
+    crash::install_panic_hook(AppConfig::state_dir(&cli.global.config));
 
-    run_app(&mut terminal, &mut app);
+    let guard = TerminalGuard::enter()?;
 
-    disable_raw_mode()?;
+    let backend = CrosstermBackend::new(io::stdout());
 
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    let mut terminal = Terminal::new(backend)?;
 
-    terminal.show_cursor()?;
+    let mut app = App::new(
+        &cli.global.config,
+        &log_options.get_path().to_string_lossy(),
+    );
+
+    let mut events = Events::new(events::TICK_RATE);
+    events::watch_config(
+        PathBuf::from(&cli.global.config),
+        events.sender(),
+        events::CONFIG_WATCH_INTERVAL,
+    );
+
+    let result = run_app(&mut terminal, &mut app, &mut events, Arc::new(LaunchPlan::run));
+
+    // The guard leaves the alternate screen first, so the error stays visible.
+    drop(guard);
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
 
     Ok(())
 }
+
+#[cfg(not(feature = "tui"))]
+fn run_tui(_: &Cli, _: &LogOptions) -> Result<(), Box<dyn Error>> {
+    eprintln!("error: this build has no TUI (the `tui` feature is off), run one of the subcommands, see --help.");
+    std::process::exit(2);
+}
@@ -1 +1,25 @@
+//! Opens Windows Terminal workspaces described by presets.
+//!
+//! The core loads, validates and edits the presets of a config file and renders
+//! them into launch plans, see the types re-exported below. The terminal UI
+//! (`workspace_opener_lib::api`, `app` and the modules they use) is behind the
+//! default `tui` feature, so a crate depending on this one with
+//! `default-features = false` gets the core without any terminal dependency.
+
 pub mod workspace_opener_lib;
+
+pub use workspace_opener_lib::{
+    form::{ FieldTarget, FieldValue, FormField },
+    model::{
+        AppConfig,
+        LaunchPlan,
+        Launcher,
+        Preset,
+        PresetInfo,
+        Settings,
+        ShellType,
+        StructureEdit,
+        TrashedPreset,
+        CONFIG,
+    },
+};
@@ -1,136 +1,321 @@
-use super::model::{ App, AppConfig, InputMode, PresetCreationHelper, State };
-use crossterm::event::{ self, Event, KeyCode };
-use std::fs::{ self };
+use super::{
+    app::{ App, InputMode, Item, PopupKind, PresetCreationHelper, State },
+    crash,
+    events::{ AppEvent, AppMessage, EventSource },
+    help,
+    keymap::{ KeyContext, KeyPress, Keymap },
+    layout::{ PaneLayout, SplitDirection },
+    line_editor::LineEditor,
+    text,
+    theme::Theme,
+    model::{ Action, AppConfig, LaunchPlan, Preset, StructureEdit, WriteType },
+};
+use std::{ sync::Arc, thread, time::{ Instant, SystemTime } };
 use tui::{
     backend::Backend,
     layout::{ Constraint, Direction, Layout, Rect },
-    style::{ Color, Modifier, Style },
+    style::{ Modifier, Style },
     text::{ Span, Spans, Text },
-    widgets::{ Block, Borders, List, ListItem, Paragraph },
+    widgets::{ Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap },
     Frame,
     Terminal,
 };
 
-const CONTROL_MODIFIER: crossterm::event::KeyModifiers = crossterm::event::KeyModifiers::CONTROL;
-const SHIFT_MODIFIER: crossterm::event::KeyModifiers = crossterm::event::KeyModifiers::SHIFT;
-pub const CONFIG: &'static str = "config.json";
-
-pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
-    let cfg_file_string = fs::read_to_string(CONFIG).unwrap();
-    let mut app_config: AppConfig = serde_json::from_str(&cfg_file_string).unwrap();
+/// Starts the program of a launch plan. `LaunchPlan::run` in the app, tests pass a stub.
+pub type Runner = Arc<dyn Fn(&LaunchPlan) -> Result<(), String> + Send + Sync>;
+
+/// Runs the TUI until it is quit, a preset is launched with `close_after_launch`
+/// or `events` runs out. Fails when the config cannot be loaded, drawing fails
+/// or the launch that closes the app fails.
+pub fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    events: &mut impl EventSource,
+    runner: Runner
+) -> Result<(), String> {
+    let mut app_config = AppConfig::load(&app.config_path)?;
     let mut pch = PresetCreationHelper::new();
 
+    match Keymap::new(&app_config.settings.keymap) {
+        Ok(keymap) => {
+            app.keymap = keymap;
+        }
+        Err(err) => {
+            log::error!("{}", err);
+            app.popup.activate_popup(&format!("{} Using the default keys.", err), PopupKind::Error);
+        }
+    }
+    if app_config.purge_expired_trash(SystemTime::now()) > 0 {
+        if let Err(err) = app_config.write_preset_to_file(&[], WriteType::Edit, &app.config_path) {
+            log::error!("{}", err);
+        }
+    }
+    match Theme::from_settings(&app_config.settings.theme) {
+        Ok(theme) => {
+            app.theme = theme;
+        }
+        Err(err) => {
+            log::error!("{}", err);
+            app.popup.activate_popup(&format!("{} Using the dark theme.", err), PopupKind::Error);
+        }
+    }
+
     loop {
         if app.state == State::RunConfig {
             let selected_item = app.items
                 .get_selected_item()
                 .expect("There is no selected item when trying to run the config.");
 
-            let (program, arg) = app_config.create_wt_command(selected_item.name.as_str()).unwrap();
-
-            run_config(program, arg);
+            match app_config.create_launch_plan(selected_item.name.as_str()) {
+                // Closing anyway, the caller reports a failed launch once the terminal is restored.
+                Ok(plan) if app_config.settings.close_after_launch => {
+                    if let Err(err) = runner(&plan) {
+                        log::error!("{}", err);
+                        return Err(err);
+                    }
+                    break;
+                }
+                // Staying open, the launch runs in the background and reports back.
+                Ok(plan) => {
+                    let sender = events.sender();
+                    let name = selected_item.name.clone();
+                    let runner = runner.clone();
+                    thread::spawn(move || {
+                        let result = runner(&plan);
+                        if let Err(err) = &result {
+                            log::error!("{}", err);
+                        }
+                        let _ = sender.send(AppEvent::Message(AppMessage::Launched { name, result }));
+                    });
 
-            break;
+                    app.handle_state_change(("", State::ChoosePreset), Some(&app_config));
+                    let message = format!("Launching '{}'...", selected_item.name);
+                    app.popup.activate_popup(&message, PopupKind::Info);
+                }
+                Err(err) => {
+                    log::error!("{}", err);
+                    app.handle_state_change(("", State::ChoosePreset), Some(&app_config));
+                    app.popup.activate_popup(&err, PopupKind::Error);
+                }
+            }
         }
 
-        terminal.draw(|f| ui(f, app)).unwrap();
-
-        let event = event::read().unwrap();
-
-        if let Event::Key(key) = event {
-            match app.input_mode {
-                InputMode::Input => {
-                    if key.modifiers == CONTROL_MODIFIER {
-                        app.handle_control_key_action(key.code);
-                        continue;
-                    } else if key.modifiers == SHIFT_MODIFIER {
-                        app.handle_shift_key_action(key.code);
-                        continue;
-                    }
+        crash::set_context(app.get_state(), &app.input_mode);
+        terminal.draw(|f| ui(f, app, &app_config)).map_err(|err| err.to_string())?;
 
-                    match key.code {
-                        KeyCode::Char(ch) => {
-                            app.insert_char(ch);
-                        }
-                        KeyCode::Backspace => {
-                            app.delete_characters();
-                        }
-                        KeyCode::Left => {
-                            app.move_cursor_left();
-                        }
-                        KeyCode::Right => {
-                            app.move_cursor_right();
-                        }
+        let Some(event) = events.next() else {
+            break;
+        };
+        let key = match event {
+            AppEvent::Key(key) => KeyPress::from(key),
+            AppEvent::Mouse(mouse) => {
+                app.handle_mouse_event(mouse, &app_config);
+                continue;
+            }
+            // The next draw takes the new size.
+            AppEvent::Resize(..) => {
+                continue;
+            }
+            AppEvent::Tick => {
+                app.handle_tick(Instant::now());
+                continue;
+            }
+            AppEvent::Message(message) => {
+                app.handle_message(message, &mut app_config);
+                continue;
+            }
+        };
 
-                        KeyCode::Enter => {
-                            app.handle_creating_preset(&mut pch, &mut app_config);
-                        }
+        if app.help_scroll.is_some() {
+            if let Some(action) = app.keymap.resolve(KeyContext::List, &mut app.pending_keys, key) {
+                app.handle_help_action(action);
+            }
+            continue;
+        }
 
-                        KeyCode::Esc => {
-                            app.cancel_preset_creation(&mut pch);
-                        }
+        if app.popup.confirmation.is_some() {
+            let action = app.keymap.resolve(KeyContext::List, &mut app.pending_keys, key);
+            let answer = match (key.get_char(), action) {
+                (Some('y' | 'Y'), _) | (_, Some(Action::Launch)) => Some(true),
+                (Some('n' | 'N'), _) | (_, Some(Action::Back | Action::Quit)) => Some(false),
+                _ => None,
+            };
+            if let Some(yes) = answer {
+                app.answer_confirmation(yes, &mut app_config);
+            }
+            continue;
+        }
 
-                        _ => {}
-                    }
+        match app.input_mode {
+            InputMode::Normal if app.state == State::LayoutEditor => {
+                // Left and right are input box actions, the editor takes them too.
+                let action = app.keymap
+                    .resolve(KeyContext::List, &mut app.pending_keys, key)
+                    .or_else(|| app.keymap.resolve(KeyContext::Text, &mut vec![], key));
+
+                // Quitting here would lose the layout, it is saved or discarded first.
+                match action {
+                    Some(Action::Help) => app.open_help(),
+                    Some(Action::Quit) | None => {}
+                    Some(action) => app.handle_layout_action(action, &mut app_config),
                 }
-                InputMode::Normal =>
-                    match key.code {
-                        KeyCode::Char('q') => {
-                            break;
-                        }
-                        KeyCode::Char('e') => {
-                            app.edit_preset(&mut app_config);
-                        }
-                        KeyCode::Down => app.items.next(),
-                        KeyCode::Up => app.items.previous(),
-                        KeyCode::Enter => {
-                            app.choose_item(&mut app_config);
-                        }
-                        KeyCode::Esc => {
-                            app.go_back(&app_config);
-                        }
-                        KeyCode::Delete => {
-                            app.handle_deleting_preset(&mut app_config);
-                        }
-                        _ => {}
+            }
+            InputMode::Normal => {
+                let Some(action) = app.keymap.resolve(
+                    KeyContext::List,
+                    &mut app.pending_keys,
+                    key
+                ) else {
+                    continue;
+                };
+
+                match action {
+                    Action::Quit => {
+                        break;
                     }
-                InputMode::Edit => {
-                    if key.modifiers == CONTROL_MODIFIER {
-                        app.handle_control_key_action(key.code);
-                        continue;
-                    } else if key.modifiers == SHIFT_MODIFIER {
-                        app.handle_shift_key_action(key.code);
-                        continue;
+                    Action::Edit => {
+                        app.edit_preset(&mut app_config);
                     }
-
-                    match key.code {
-                        KeyCode::Char(ch) => {
-                            app.insert_char(ch);
-                        }
-                        KeyCode::Backspace => {
-                            app.delete_characters();
-                        }
-                        KeyCode::Left => {
-                            app.move_cursor_left();
-                        }
-                        KeyCode::Right => {
-                            app.move_cursor_right();
+                    Action::Search => {
+                        app.start_filter();
+                    }
+                    Action::Help => {
+                        app.open_help();
+                    }
+                    Action::Down => app.items.next(),
+                    Action::Up => app.items.previous(),
+                    Action::First => app.items.first(),
+                    Action::Last => app.items.last(),
+                    Action::Launch if app.state == State::Trash => {
+                        app.restore_preset(&mut app_config);
+                    }
+                    Action::Launch => {
+                        app.choose_item(&app_config);
+                    }
+                    Action::Back => {
+                        app.go_back(&app_config);
+                    }
+                    Action::Delete if app.state == State::EditPreset => {
+                        app.edit_structure(StructureEdit::Delete, &mut app_config);
+                    }
+                    Action::Delete => {
+                        app.handle_deleting_preset(&app_config);
+                    }
+                    Action::InsertBefore => {
+                        app.edit_structure(StructureEdit::InsertBefore, &mut app_config);
+                    }
+                    Action::InsertAfter => {
+                        app.edit_structure(StructureEdit::InsertAfter, &mut app_config);
+                    }
+                    Action::Duplicate => {
+                        app.edit_structure(StructureEdit::Duplicate, &mut app_config);
+                    }
+                    Action::MoveLeft => {
+                        app.edit_structure(StructureEdit::MoveLeft, &mut app_config);
+                    }
+                    Action::MoveRight => {
+                        app.edit_structure(StructureEdit::MoveRight, &mut app_config);
+                    }
+                    Action::Layout => {
+                        app.open_layout_editor(&app_config);
+                    }
+                    Action::Toggle => {
+                        app.toggle_field(&mut app_config);
+                    }
+                    _ => {}
+                }
+            }
+            InputMode::Select => {
+                // Left and right are input box actions, the dropdown takes them too.
+                let action = app.keymap
+                    .resolve(KeyContext::List, &mut app.pending_keys, key)
+                    .or_else(|| app.keymap.resolve(KeyContext::Text, &mut vec![], key));
+
+                // Like the layout editor, the field is saved or discarded before quitting.
+                match action {
+                    Some(Action::Help) => app.open_help(),
+                    Some(Action::Launch) => app.handle_editing_preset(&mut app_config),
+                    Some(Action::Back) => {
+                        app.handle_state_change(("", app.previous_state), Some(&app_config));
+                    }
+                    Some(Action::Down | Action::Right | Action::Toggle) => app.cycle_field(true),
+                    Some(Action::Up | Action::Left) => app.cycle_field(false),
+                    _ => {}
+                }
+            }
+            InputMode::Input | InputMode::Edit | InputMode::Filter => {
+                let input_before = app.input.text().to_string();
+
+                match app.keymap.resolve(KeyContext::Text, &mut app.pending_keys, key) {
+                    Some(Action::Submit) =>
+                        match app.input_mode {
+                            InputMode::Input => {
+                                app.handle_creating_preset(&mut pch, &mut app_config);
+                            }
+                            InputMode::Edit => {
+                                app.handle_editing_preset(&mut app_config);
+                            }
+                            _ => {
+                                app.choose_item(&app_config);
+                            }
                         }
-                        KeyCode::Enter => {
-                            app.handle_editing_preset(&mut app_config);
+                    Some(Action::Cancel) =>
+                        match app.input_mode {
+                            InputMode::Input => {
+                                app.cancel_preset_creation(&mut pch);
+                            }
+                            InputMode::Edit => {
+                                app.handle_state_change(
+                                    ("", app.previous_state),
+                                    Some(&app_config)
+                                );
+                            }
+                            _ => {
+                                app.clear_filter(&app_config);
+                            }
                         }
-                        KeyCode::Esc => {
-                            app.handle_state_change(("", app.previous_state), Some(&app_config));
+                    Some(Action::PreviousStep) => {
+                        if let InputMode::Input = app.input_mode {
+                            app.step_back_preset_creation(&mut pch);
                         }
-                        _ => {}
                     }
+                    Some(action) => app.handle_text_action(action),
+                    None =>
+                        match key.get_char() {
+                            Some(ch) => app.insert_char(ch),
+                            // Keys that do not type text can still open the help, move
+                            // through the filtered list and cycle the wizard's choices.
+                            None =>
+                                match app.keymap.resolve(KeyContext::List, &mut vec![], key) {
+                                    Some(Action::Help) => app.open_help(),
+                                    Some(Action::Down) if
+                                        matches!(app.input_mode, InputMode::Filter)
+                                    => app.items.next(),
+                                    Some(Action::Up) if
+                                        matches!(app.input_mode, InputMode::Filter)
+                                    => app.items.previous(),
+                                    Some(Action::Down) if
+                                        matches!(app.input_mode, InputMode::Input)
+                                    => app.cycle_choice(true),
+                                    Some(Action::Up) if
+                                        matches!(app.input_mode, InputMode::Input)
+                                    => app.cycle_choice(false),
+                                    _ => {}
+                                }
+                        }
+                }
+
+                if matches!(app.input_mode, InputMode::Filter) && app.input.text() != input_before {
+                    app.apply_filter(&app_config);
                 }
             }
         }
     }
+
+    Ok(())
 }
 
-fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App, app_config: &AppConfig) {
     let size = f.size();
 
     let chunks = Layout::default()
@@ -138,79 +323,103 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
         .constraints([Constraint::Length(1), Constraint::Min(1), Constraint::Length(3)].as_ref())
         .split(f.size());
 
-    let edit_color = Color::Rgb(51, 153, 255);
-
-    let mut controls = vec![
-        Span::raw("Press "),
-        Span::styled("ESC", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" to go back"),
-        Span::styled(", q", Style::default().add_modifier(Modifier::BOLD)),
-        Span::raw(" to exit")
-    ];
-
-    let main_block = Block::default().borders(Borders::ALL);
-    let mut main_block_style = Style::default().bg(Color::Black);
-    let highlight_style = Style::default().bg(edit_color);
+    let theme = app.theme.clone();
+
+    let typing = !matches!(app.input_mode, InputMode::Normal | InputMode::Select);
+    let mut control_actions = match app.input_mode {
+        InputMode::Normal => vec![(Action::Back, " to go back"), (Action::Quit, " to exit")],
+        InputMode::Select =>
+            vec![
+                (Action::Launch, " to save"),
+                (Action::Back, " to go back"),
+                (Action::Toggle, " for the next value")
+            ],
+        InputMode::Input =>
+            vec![(Action::Cancel, " to go back"), (Action::PreviousStep, " for the previous question")],
+        _ => vec![(Action::Cancel, " to go back")],
+    };
+
+    let main_block = Block::default().borders(Borders::ALL).border_style(theme.border());
+    let mut main_block_style = theme.background();
+    let highlight_style = theme.highlight();
 
     let input_block = Block::default().title("Input").borders(Borders::ALL);
 
     match app.state {
+        State::EditPreset if matches!(app.input_mode, InputMode::Normal) => {
+            main_block_style = main_block_style.patch(theme.edit());
+            control_actions.push((Action::InsertAfter, " to add a tab/window"));
+            control_actions.push((Action::Delete, " to delete it"));
+            control_actions.push((Action::Layout, " to arrange the tab"));
+        }
+        State::LayoutEditor => {
+            control_actions = vec![
+                (Action::Launch, " to save"),
+                (Action::Back, " to discard"),
+                (Action::SplitVertical, " to split side by side"),
+                (Action::SplitHorizontal, " to stack")
+            ];
+        }
         State::EditPreset | State::ChangeFieldName => {
-            main_block_style = main_block_style.fg(edit_color);
+            main_block_style = main_block_style.patch(theme.edit());
         }
-        State::ChoosePreset => {
-            controls.push(Span::styled(", E", Style::default().add_modifier(Modifier::BOLD)));
-            controls.push(Span::raw(" to edit"));
-            controls.push(Span::styled(", DEL", Style::default().add_modifier(Modifier::BOLD)));
-            controls.push(Span::raw(" to delete"));
+        State::ChoosePreset if matches!(app.input_mode, InputMode::Normal) => {
+            control_actions.push((Action::Edit, " to edit"));
+            control_actions.push((Action::Delete, " to delete"));
+            control_actions.push((Action::Search, " to search"));
+        }
+        State::Trash => {
+            control_actions.push((Action::Launch, " to restore"));
+            control_actions.push((Action::Delete, " to delete for good"));
         }
         _ => {}
     }
 
-    if app.popup.active {
-        let popup_block = Block::default().borders(Borders::ALL);
-        let area = centered_rect(60, 20, size);
-        let popup_message = Paragraph::new(Span::from(app.popup.message.to_string())).style(
-            Style::default().fg(app.popup.color)
-        );
-        f.render_widget(popup_message.block(popup_block), area);
-    }
+    control_actions.push((Action::Help, " for help"));
+
+    let mut controls = create_controls(&app.keymap, typing, &control_actions);
 
     if app.debug_mode {
+        let [state_style, input_mode_style, cursor_style, selected_style] = theme.debug();
+
         controls.push(Span::raw(", State:"));
-        controls.push(
-            Span::styled(
-                format!(" {:?}", app.get_state()),
-                Style::default().add_modifier(Modifier::BOLD).fg(Color::Red)
-            )
-        );
+        controls.push(Span::styled(format!(" {:?}", app.get_state()), state_style));
 
         controls.push(Span::raw(", InputMode:"));
-        controls.push(
-            Span::styled(
-                format!(" {:?}", app.input_mode),
-                Style::default().add_modifier(Modifier::BOLD).fg(Color::LightYellow)
-            )
-        );
+        controls.push(Span::styled(format!(" {:?}", app.input_mode), input_mode_style));
 
         controls.push(Span::raw(", Cursor:"));
-        controls.push(
-            Span::styled(
-                format!(" {:?}", app.cursor_idx),
-                Style::default().add_modifier(Modifier::BOLD).fg(Color::LightGreen)
-            )
-        );
+        controls.push(Span::styled(format!(" {:?}", app.input.cursor()), cursor_style));
 
         controls.push(Span::raw(", Selected::"));
-        controls.push(
-            Span::styled(
-                format!(" {:?}", app.selected_input),
-                Style::default().add_modifier(Modifier::BOLD).fg(Color::Magenta)
-            )
-        );
+        controls.push(Span::styled(format!(" {:?}", app.input.selection()), selected_style));
     }
 
     match app.input_mode {
+        InputMode::Normal if app.state == State::LayoutEditor => {
+            if let Some(editor) = &app.layout_editor {
+                let block = main_block
+                    .title(format!("Layout of tab #{}", editor.get_tab() + 1))
+                    .style(main_block_style);
+                let area = block.inner(chunks[1]);
+                f.render_widget(block, chunks[1]);
+                draw_pane_layout(
+                    f,
+                    area,
+                    editor.get_layout(),
+                    editor.get_args(),
+                    Some(editor.get_focus()),
+                    &theme
+                );
+            }
+            f.render_widget(input_block, chunks[2]);
+            controls.push(Span::raw("."));
+        }
+        InputMode::Input if app.reviewing => {
+            let preset = app.created_preset();
+            draw_preset_review(f, chunks[1], &preset, &app.keymap, &theme);
+            f.render_widget(input_block, chunks[2]);
+        }
         InputMode::Input => {
             let mut prompts: Vec<ListItem> = Vec::new();
 
@@ -222,43 +431,80 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
                 if let Some(msg) = app.messages.get(index) {
                     prompts.push(
                         ListItem::new(Span::from(msg.as_str())).style(
-                            Style::default().fg(edit_color).add_modifier(Modifier::ITALIC)
+                            theme.edit().add_modifier(Modifier::ITALIC)
                         )
                     );
                 }
             }
+            if let Some(err) = &app.creation_error {
+                prompts.push(
+                    ListItem::new(Span::from(err.as_str())).style(theme.popup(PopupKind::Error))
+                );
+            }
+            if !app.choices.is_empty() {
+                let keys = [Action::Up, Action::Down]
+                    .into_iter()
+                    .filter_map(|action| app.keymap.labels_for(action, true).into_iter().next())
+                    .collect::<Vec<String>>()
+                    .join(" / ");
+                let mut choices = vec![Span::raw(format!("Choices ({}): ", keys))];
+                for (index, choice) in app.choices.iter().enumerate() {
+                    if index > 0 {
+                        choices.push(Span::raw(" | "));
+                    }
+                    let name = match choice.is_empty() {
+                        true => "default",
+                        false => choice.as_str(),
+                    };
+                    match choice == app.input.text() {
+                        true => choices.push(Span::styled(name, theme.highlight())),
+                        false => choices.push(Span::raw(name)),
+                    }
+                }
+                prompts.push(ListItem::new(Spans::from(choices)));
+            }
 
-            let prompts = List::new(prompts).block(main_block.clone());
-
-            let input_block = input_block.style(Style::default().fg(edit_color));
-
-            let spans = create_spans(&app.input, &app.selected_input, edit_color);
-
-            let user_input = Paragraph::new(Text::from(Spans::from(spans)));
+            // Keep the current question in view once the answers fill the screen.
+            let height = chunks[1].height.saturating_sub(2) as usize;
+            let hidden = prompts.len().saturating_sub(height);
+            let prompts = List::new(prompts.split_off(hidden)).block(main_block.clone());
 
             f.render_widget(prompts, chunks[1]);
-            f.render_widget(user_input.block(input_block), chunks[2]);
-
-            f.set_cursor(chunks[2].x + (app.cursor_idx as u16) + 1, chunks[2].y + 1);
+            draw_input(f, chunks[2], input_block, &mut app.input, &theme);
         }
         _ => {
             let items = app.items.items
                 .iter()
                 .map(|item| {
-                    let _lines = vec![Spans::from(item.name.as_str())];
-                    ListItem::new(_lines).style(Style::default().fg(Color::White))
+                    ListItem::new(vec![create_item_spans(item, &theme)]).style(theme.text())
                 })
                 .collect::<Vec<ListItem>>();
 
-            if let InputMode::Edit = app.input_mode {
-                let input_block = input_block.style(Style::default().fg(edit_color));
-                let spans = create_spans(&app.input, &app.selected_input, edit_color);
-                let user_input = Paragraph::new(Text::from(Spans::from(spans)));
-
-                f.render_widget(user_input.block(input_block), chunks[2]);
-                f.set_cursor(chunks[2].x + (app.cursor_idx as u16) + 1, chunks[2].y + 1);
-            } else {
-                f.render_widget(input_block, chunks[2]);
+            match app.input_mode {
+                InputMode::Edit | InputMode::Filter => {
+                    let input_block = match (&app.input_mode, app.field_error()) {
+                        (InputMode::Filter, _) => input_block.title("Filter"),
+                        (_, Some(err)) =>
+                            input_block.title(Span::styled(err, theme.popup(PopupKind::Error))),
+                        _ => input_block,
+                    };
+                    draw_input(f, chunks[2], input_block, &mut app.input, &theme);
+                }
+                InputMode::Select => {
+                    let field = app.items.get_selected_item().and_then(|item| item.field);
+                    let input_block = match (app.field_error(), &field) {
+                        (Some(err), _) =>
+                            input_block.title(Span::styled(err, theme.popup(PopupKind::Error))),
+                        (None, Some(field)) => input_block.title(field.get_label().to_string()),
+                        _ => input_block,
+                    };
+                    let value = field.map(|field| field.get_value().to_string()).unwrap_or_default();
+                    let value = Paragraph::new(Span::styled(format!("< {} >", value), theme.edit()));
+                    f.render_widget(value.block(input_block.style(theme.edit())), chunks[2]);
+                }
+                _ => {
+                    f.render_widget(input_block, chunks[2]);
+                }
             }
 
             let items = List::new(items)
@@ -269,52 +515,424 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
 
             controls.push(Span::raw("."));
 
-            f.render_stateful_widget(items, chunks[1], &mut app.items.list_state);
+            let selected_preset = app.items
+                .get_selected_item()
+                .filter(|item| item.leading_state == State::RunConfig)
+                .and_then(|item| app_config.get_preset_by_name(&item.name));
+
+            let list_area = match selected_preset {
+                Some(preset) => {
+                    let areas = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+                        .split(chunks[1]);
+
+                    draw_preset_details(f, areas[1], preset, &theme);
+                    areas[0]
+                }
+                None => chunks[1],
+            };
+
+            f.render_stateful_widget(items, list_area, &mut app.items.list_state);
+
+            app.areas.list = list_area;
+            app.items.update_offset(list_area.height.saturating_sub(2) as usize);
+
+            if let (InputMode::Select, Some((options, selected))) = (&app.input_mode, app.field_options()) {
+                draw_dropdown(f, list_area, &options, selected, &theme);
+            }
         }
     }
+    app.areas.input = chunks[2];
+
     let controls = Paragraph::new(Text::from(Spans::from(controls)));
 
     f.render_widget(controls, chunks[0]);
+
+    // Drawn after the panes, so none of them shows through the message.
+    app.areas.popup = None;
+    if app.popup.active {
+        let popup_block = Block::default().borders(Borders::ALL).border_style(theme.border());
+        let area = centered_rect(60, 20, size);
+        app.areas.popup = Some(area);
+        let popup_message = Paragraph::new(Span::from(app.popup.message.to_string()))
+            .style(theme.popup(app.popup.kind))
+            .wrap(Wrap { trim: true });
+        f.render_widget(Clear, area);
+        f.render_widget(popup_message.block(popup_block), area);
+    }
+
+    if app.help_scroll.is_some() {
+        draw_help(f, app, &theme);
+    }
+}
+
+fn draw_help<B: Backend>(f: &mut Frame<B>, app: &mut App, theme: &Theme) {
+    let sections = help::help_sections(&app.keymap, app.state, &app.input_mode);
+
+    let key_width = sections
+        .iter()
+        .flat_map(|section| section.get_entries())
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+
+    let mut lines: Vec<Spans> = Vec::new();
+    for section in sections.iter() {
+        if !lines.is_empty() {
+            lines.push(Spans::default());
+        }
+        lines.push(Spans::from(Span::styled(section.get_title().to_string(), theme.edit().patch(bold))));
+        for (keys, description) in section.get_entries() {
+            lines.push(
+                Spans::from(
+                    vec![
+                        Span::styled(format!("  {:<width$}  ", keys, width = key_width), bold),
+                        Span::raw(description.clone())
+                    ]
+                )
+            );
+        }
+    }
+
+    let title = match app.keymap.label(Action::Back) {
+        Some(key) => format!("Help - {} to close", key),
+        None => String::from("Help"),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(theme.border())
+        .style(theme.background());
+
+    let area = centered_rect(70, 80, f.size());
+    let max_scroll = lines.len().saturating_sub(block.inner(area).height as usize) as u16;
+    let scroll = app.help_scroll.unwrap_or(0).min(max_scroll);
+    app.help_scroll = Some(scroll);
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+}
+
+fn draw_preset_review<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    preset: &Preset,
+    keymap: &Keymap,
+    theme: &Theme
+) {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let key = |action: Action| keymap.labels_for(action, true).into_iter().next().unwrap_or_default();
+
+    let mut lines = vec![
+        Spans::from(vec![Span::styled("Name: ", bold), Span::raw(preset.name.as_str())]),
+        Spans::from(vec![Span::styled("Tabs: ", bold), Span::raw(preset.tabs.to_string())]),
+        Spans::from(
+            vec![
+                Span::styled("Profile: ", bold),
+                Span::raw(match preset.preset_info.wt_profile.is_empty() {
+                    true => "default",
+                    false => preset.preset_info.wt_profile.as_str(),
+                })
+            ]
+        ),
+        Spans::from(
+            vec![
+                Span::styled("Shells: ", bold),
+                Span::raw(
+                    format!(
+                        "{} -> {}",
+                        preset.preset_info.init_shell,
+                        preset.preset_info.target_shell
+                    )
+                )
+            ]
+        )
+    ];
+    for (tab, windows) in preset.windows.iter().enumerate() {
+        lines.push(
+            Spans::from(
+                vec![
+                    Span::styled(format!("Tab #{}: ", tab + 1), bold),
+                    Span::raw(format!("{} windows", windows))
+                ]
+            )
+        );
+        for (window, args) in preset.get_tab_args(tab).unwrap_or(&[]).iter().enumerate() {
+            lines.push(Spans::from(format!("  {}. {}", window + 1, args)));
+        }
+    }
+    lines.push(Spans::default());
+    lines.push(
+        Spans::from(
+            vec![
+                Span::raw("Press "),
+                Span::styled(key(Action::Submit), bold),
+                Span::raw(" to create the preset, "),
+                Span::styled(key(Action::PreviousStep), bold),
+                Span::raw(" to change the last answer or "),
+                Span::styled(key(Action::Cancel), bold),
+                Span::raw(" to cancel.")
+            ]
+        )
+    );
+
+    let block = Block::default()
+        .title("Review")
+        .borders(Borders::ALL)
+        .border_style(theme.border())
+        .style(theme.background());
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), area);
 }
 
-fn create_spans<'a>(input: &'a str, indices: &'a [usize], edit_color: Color) -> Vec<Span<'a>> {
-    let mut spans: Vec<Span<'a>> = vec![];
+fn draw_preset_details<B: Backend>(f: &mut Frame<B>, area: Rect, preset: &Preset, theme: &Theme) {
+    let block = Block::default()
+        .title("Details")
+        .borders(Borders::ALL)
+        .border_style(theme.border())
+        .style(theme.background());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let wt_profile = match preset.preset_info.wt_profile.is_empty() {
+        true => "default",
+        false => preset.preset_info.wt_profile.as_str(),
+    };
+
+    let mut info = vec![
+        Spans::from(
+            vec![
+                Span::styled("Tabs: ", bold),
+                Span::raw(format!("{}, ", preset.tabs)),
+                Span::styled("windows: ", bold),
+                Span::raw(preset.args.len().to_string())
+            ]
+        ),
+        Spans::from(vec![Span::styled("Profile: ", bold), Span::raw(wt_profile)]),
+        Spans::from(
+            vec![
+                Span::styled("Shells: ", bold),
+                Span::raw(
+                    format!(
+                        "{} -> {}",
+                        preset.preset_info.init_shell,
+                        preset.preset_info.target_shell
+                    )
+                )
+            ]
+        )
+    ];
+    if !preset.tags.is_empty() {
+        info.push(Spans::from(vec![Span::styled("Tags: ", bold), Span::raw(preset.tags.join(", "))]));
+    }
 
-    if indices.len() > 0 {
-        let first_select_idx = *indices.first().unwrap();
-        let last_selected_idx = *indices.last().unwrap();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(info.len() as u16 + 1), Constraint::Min(0)].as_ref())
+        .split(inner);
 
-        if indices.len() == input.len() {
-            spans.push(Span::styled(input, Style::default().bg(Color::Cyan)));
-        } else if first_select_idx > 0 {
-            let substr_before = &input[0..first_select_idx];
-            spans.push(Span::styled(substr_before, Style::default().fg(edit_color)));
+    f.render_widget(Paragraph::new(info), chunks[0]);
 
-            let substr_highlighted = &input[first_select_idx..=last_selected_idx];
-            spans.push(Span::styled(substr_highlighted, Style::default().bg(Color::Cyan)));
+    let tabs = preset.windows.len() as u32;
+    let constraints = preset.windows
+        .iter()
+        .map(|_| Constraint::Ratio(1, tabs))
+        .collect::<Vec<Constraint>>();
+    let tab_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(chunks[1]);
 
-            if last_selected_idx != input.len() - 1 {
-                let substr_after = &input[last_selected_idx + 1..];
-                spans.push(Span::styled(substr_after, Style::default().fg(edit_color)));
-            }
-        } else {
-            let substr_highlighted = &input[..=last_selected_idx];
-            let substr_after = &input[last_selected_idx + 1..];
-            spans.push(Span::styled(substr_highlighted, Style::default().bg(Color::Cyan)));
-            spans.push(Span::styled(substr_after, Style::default().fg(edit_color)));
+    for (tab, area) in tab_areas.iter().enumerate() {
+        let tab_block = Block::default().title(Span::styled(format!("Tab #{}", tab + 1), bold));
+        let tab_area = tab_block.inner(*area);
+        f.render_widget(tab_block, *area);
+
+        let args = preset.get_tab_args(tab).unwrap_or(&[]);
+        draw_pane_layout(f, tab_area, &preset.layout(tab), args, None, theme);
+    }
+}
+
+fn draw_pane_layout<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    layout: &PaneLayout,
+    args: &[String],
+    focus: Option<usize>,
+    theme: &Theme
+) {
+    match layout {
+        PaneLayout::Pane(index) => {
+            let border_style = match focus == Some(*index) {
+                true => theme.highlight(),
+                false => theme.border(),
+            };
+            let block = Block::default()
+                .title(format!("{}", index + 1))
+                .borders(Borders::ALL)
+                .border_style(border_style);
+            let commands = args
+                .get(*index)
+                .map(|arg| {
+                    arg.split(',')
+                        .map(|command| Spans::from(command.trim().to_string()))
+                        .collect::<Vec<Spans>>()
+                })
+                .unwrap_or_default();
+
+            let pane = Paragraph::new(commands)
+                .block(block)
+                .wrap(Wrap { trim: true });
+            f.render_widget(pane, area);
+        }
+        PaneLayout::Split { direction, ratio, first, second } => {
+            let direction = match direction {
+                SplitDirection::Vertical => Direction::Horizontal,
+                SplitDirection::Horizontal => Direction::Vertical,
+            };
+            let areas = Layout::default()
+                .direction(direction)
+                .constraints(
+                    [Constraint::Percentage(*ratio), Constraint::Percentage(100 - ratio)].as_ref()
+                )
+                .split(area);
+
+            draw_pane_layout(f, areas[0], first, args, focus, theme);
+            draw_pane_layout(f, areas[1], second, args, focus, theme);
         }
-    } else {
-        spans.push(Span::styled(input, Style::default().fg(edit_color)));
     }
+}
 
-    spans
+/// "Press ESC to go back, q to exit" with the keys currently bound to the actions.
+/// While `typing`, keys that would type text are not shown.
+fn create_controls(
+    keymap: &Keymap,
+    typing: bool,
+    actions: &[(Action, &'static str)]
+) -> Vec<Span<'static>> {
+    let mut controls = vec![Span::raw("Press ")];
+
+    let bound = actions.iter().filter_map(|(action, text)| {
+        keymap
+            .labels_for(*action, typing)
+            .into_iter()
+            .next()
+            .map(|key| (key, *text))
+    });
+
+    for (index, (key, text)) in bound.enumerate() {
+        if index > 0 {
+            controls.push(Span::raw(", "));
+        }
+        controls.push(Span::styled(key, Style::default().add_modifier(Modifier::BOLD)));
+        controls.push(Span::raw(text));
+    }
+
+    controls
 }
-fn run_config(program: String, arg: String) {
-    let mut _process = std::process::Command
-        ::new(program)
-        .arg(arg)
-        .spawn()
-        .expect("Failed to launch the target process.");
+
+fn create_item_spans<'a>(item: &'a Item, theme: &Theme) -> Spans<'a> {
+    if item.matched_indices.is_empty() {
+        return Spans::from(item.name.as_str());
+    }
+
+    let matched_style = theme.matched();
+
+    let spans = item.name
+        .chars()
+        .enumerate()
+        .map(|(index, ch)| {
+            match item.matched_indices.contains(&index) {
+                true => Span::styled(ch.to_string(), matched_style),
+                false => Span::raw(ch.to_string()),
+            }
+        })
+        .collect::<Vec<Span>>();
+
+    Spans::from(spans)
+}
+
+/// Draws the options of the field being picked over the bottom of `area`, right
+/// above the input box, with the current one highlighted.
+fn draw_dropdown<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    options: &[String],
+    selected: usize,
+    theme: &Theme
+) {
+    let names = options
+        .iter()
+        .map(|option| {
+            match option.is_empty() {
+                true => "default",
+                false => option.as_str(),
+            }
+        })
+        .collect::<Vec<&str>>();
+    let width = names
+        .iter()
+        .map(|name| text::display_width(name, text::grapheme_count(name)) as u16)
+        .max()
+        .unwrap_or(0)
+        .saturating_add(6)
+        .max(20)
+        .min(area.width.saturating_sub(2));
+    let height = (names.len() as u16).saturating_add(2).min(area.height.saturating_sub(2));
+    let dropdown_area = Rect::new(
+        area.x + 2,
+        (area.y + area.height).saturating_sub(height + 1),
+        width,
+        height
+    );
+
+    let items = names
+        .into_iter()
+        .map(|name| ListItem::new(Span::from(name)))
+        .collect::<Vec<ListItem>>();
+    let dropdown = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(theme.border()))
+        .style(theme.text())
+        .highlight_style(theme.highlight())
+        .highlight_symbol("> ");
+    let mut state = ListState::default();
+    state.select(Some(selected));
+
+    f.render_widget(Clear, dropdown_area);
+    f.render_stateful_widget(dropdown, dropdown_area, &mut state);
+}
+
+/// The visible part of the input box text with the selection highlighted, and the cursor.
+fn draw_input<B: Backend>(
+    f: &mut Frame<B>,
+    area: Rect,
+    block: Block,
+    input: &mut LineEditor,
+    theme: &Theme
+) {
+    let visible = input.scroll_to_cursor(area.width.saturating_sub(2) as usize);
+    let selection = input.selection().unwrap_or(0..0);
+
+    let spans = text
+        ::graphemes(input.text())
+        .into_iter()
+        .enumerate()
+        .skip(visible.start)
+        .take(visible.len())
+        .map(|(index, grapheme)| {
+            match selection.contains(&index) {
+                true => Span::styled(grapheme, theme.selection()),
+                false => Span::styled(grapheme, theme.edit()),
+            }
+        })
+        .collect::<Vec<Span>>();
+
+    let user_input = Paragraph::new(Text::from(Spans::from(spans)));
+    f.render_widget(user_input.block(block.style(theme.edit())), area);
+
+    f.set_cursor(area.x + (input.cursor_column() as u16) + 1, area.y + 1);
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
@@ -0,0 +1,1537 @@
+use super::{
+    model::{
+        Action,
+        AppConfig,
+        Preset,
+        ShellType,
+        StructureEdit,
+        CONFIG,
+    },
+    events::AppMessage,
+    doctor,
+    form::{ FieldTarget, FieldValue, FormField },
+    fuzzy::FuzzyMatch,
+    keymap::{ KeyPress, Keymap },
+    layout::{ FocusDirection, LayoutEditor, SplitDirection },
+    line_editor::LineEditor,
+    logging,
+    theme::Theme,
+};
+use crossterm::event::{ MouseButton, MouseEvent, MouseEventKind };
+use log::error;
+use std::{ collections::VecDeque, time::{ Duration, Instant, SystemTime } };
+use tui::{ layout::Rect, widgets::ListState };
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum State {
+    Start,
+    Settings,
+    ChoosePreset,
+    CreatePreset,
+    EditPreset,
+    ChangeFieldName,
+    RunConfig,
+    Doctor,
+    Trash,
+    LayoutEditor,
+}
+#[derive(Debug)]
+pub enum InputMode {
+    Normal,
+    Input,
+    Edit,
+    /// Picks the value of a choice or a toggle field from a dropdown.
+    Select,
+    Filter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub(super) name: String,
+    pub(super) leading_state: State,
+    /// The value edited in `State::ChangeFieldName` when the item is chosen.
+    pub(super) field: Option<FormField>,
+    /// Char indices of `name` matched by the ChoosePreset filter.
+    pub(super) matched_indices: Vec<usize>,
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopupKind {
+    Info,
+    Success,
+    Error,
+}
+
+/// An action waiting for a yes/no answer in the popup.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Confirmation {
+    DeletePreset(String),
+    /// Launches the preset with the given name.
+    LaunchPreset(String),
+    /// Removes the trashed preset at the given index for good.
+    PurgePreset(usize),
+}
+
+pub struct Popup {
+    pub(super) active: bool,
+    pub(super) message: String,
+    pub(super) kind: PopupKind,
+    pub(super) confirmation: Option<Confirmation>,
+    /// When the message was shown, messages close on their own after a while.
+    pub(super) shown_at: Option<Instant>,
+}
+
+pub struct StatefulList {
+    pub(super) list_state: ListState,
+    pub(super) items: Vec<Item>,
+    /// First visible item, kept in step with the private offset of `list_state`.
+    pub(super) offset: usize,
+}
+
+/// Where `ui` drew the parts that react to the mouse during the last frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScreenAreas {
+    pub(super) list: Rect,
+    pub(super) input: Rect,
+    pub(super) popup: Option<Rect>,
+}
+
+pub struct App {
+    pub(super) state: State,
+    pub(super) previous_state: State,
+    pub(super) items: StatefulList,
+    pub(super) prompts: Vec<String>,
+    pub(super) input: LineEditor,
+    pub(super) input_mode: InputMode,
+    pub(super) messages: Vec<String>,
+    /// Why the last answer of the Create Preset wizard was rejected.
+    pub(super) creation_error: Option<String>,
+    /// Every question is answered and the preset is shown for a last check.
+    pub(super) reviewing: bool,
+    /// Answers offered for the current question of the wizard, empty for free text.
+    pub(super) choices: Vec<String>,
+    pub(super) popup: Popup,
+    pub(super) current_preset: Option<Preset>,
+    pub(super) debug_mode: bool,
+    pub(super) config_path: String,
+    pub(super) log_path: String,
+    pub(super) areas: ScreenAreas,
+    /// Time and item index of the last left click, used to detect double clicks.
+    pub(super) last_click: Option<(Instant, usize)>,
+    pub(super) keymap: Keymap,
+    pub(super) theme: Theme,
+    /// Keys typed so far of a multi key binding such as `g g`.
+    pub(super) pending_keys: Vec<KeyPress>,
+    /// Scroll offset of the help overlay while it is open.
+    pub(super) help_scroll: Option<u16>,
+    /// The tab being arranged in `State::LayoutEditor`.
+    pub(super) layout_editor: Option<LayoutEditor>,
+}
+
+pub struct PresetCreationHelper {
+    pub(super) windows: VecDeque<u8>,
+    pub(super) max_windows: usize,
+}
+
+impl Item {
+    pub fn new(name: String, leading_state: State, field: Option<FormField>) -> Item {
+        Item {
+            name,
+            leading_state,
+            field,
+            matched_indices: Vec::new(),
+        }
+    }
+}
+
+impl Preset {
+    /// The fields of `form_fields` as the items of the Edit Preset list.
+    pub fn into_items(&self) -> Vec<Item> {
+        self.form_fields()
+            .into_iter()
+            .map(|field| Item::new(field.item_name(), State::ChangeFieldName, Some(field)))
+            .collect()
+    }
+}
+
+impl State {
+    fn create_items(&self, app_config: Option<&AppConfig>, config_path: &str) -> Option<Vec<Item>> {
+        match self {
+            State::Start =>
+                Some(
+                    vec![
+                        Item::new("Choose Preset".to_string(), State::ChoosePreset, None),
+                        Item::new("Create Preset".to_string(), State::CreatePreset, None),
+                        Item::new("Settings".to_string(), State::Settings, None),
+                        Item::new("Doctor".to_string(), State::Doctor, None)
+                    ]
+                ),
+            State::Settings => {
+                let app_config = app_config.unwrap();
+                let mut items = app_config.settings
+                    .form_fields()
+                    .into_iter()
+                    .map(|field| Item::new(field.item_name(), State::ChangeFieldName, Some(field)))
+                    .collect::<Vec<Item>>();
+                // Given on the command line, so it is shown but not edited here.
+                items.push(Item::new(format!("Config file: {}", config_path), State::Settings, None));
+                items.push(
+                    Item::new(
+                        format!("Trash ({} presets)", app_config.trash.len()),
+                        State::Trash,
+                        None
+                    )
+                );
+                Some(items)
+            }
+            _ => None,
+        }
+    }
+
+    fn create_prompts(&self) -> Option<Vec<String>> {
+        match self {
+            State::CreatePreset =>
+                Some(
+                    vec!["Enter preset name:".to_string(), "Enter tabs amount (1-10):".to_string()]
+                ),
+            _ => None,
+        }
+    }
+}
+
+impl StatefulList {
+    fn with_items(items: Vec<Item>) -> Self {
+        let index = match items.len() {
+            0 => None,
+            _ => Some(0),
+        };
+
+        let mut list = StatefulList {
+            list_state: ListState::default(),
+            items,
+            offset: 0,
+        };
+
+        list.list_state.select(index);
+        list
+    }
+
+    pub fn next(&mut self) {
+        let i = match self.list_state.selected() {
+            Some(i) => {
+                if i >= self.items.len() - 1 { self.items.len() - 1 } else { i + 1 }
+            }
+            None => 0,
+        };
+
+        self.list_state.select(Some(i))
+    }
+
+    pub fn previous(&mut self) {
+        let i = match self.list_state.selected() {
+            Some(i) => {
+                if i == 0 { 0 } else { i - 1 }
+            }
+            None => 0,
+        };
+
+        self.list_state.select(Some(i))
+    }
+
+    pub fn first(&mut self) {
+        if !self.items.is_empty() {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    pub fn last(&mut self) {
+        if !self.items.is_empty() {
+            self.list_state.select(Some(self.items.len() - 1));
+        }
+    }
+
+    pub fn get_selected_item(&self) -> Option<Item> {
+        self.list_state.selected().and_then(|i| self.items.get(i).cloned())
+    }
+
+    pub fn get_selected_item_index(&self) -> Option<usize> {
+        self.list_state.selected()
+    }
+
+    /// Scrolls the same way tui does when rendering a `List` of one line items
+    /// into `height` rows.
+    pub fn update_offset(&mut self, height: usize) {
+        if self.items.is_empty() || height == 0 {
+            return;
+        }
+
+        let last = self.items.len() - 1;
+        let selected = self.list_state.selected().unwrap_or(0).min(last);
+        let mut offset = self.offset.min(last);
+
+        if selected >= offset + height {
+            offset = selected + 1 - height;
+        }
+        if selected < offset {
+            offset = selected;
+        }
+
+        self.offset = offset;
+    }
+
+    /// Index of the item drawn on the given row of the list's inner area.
+    pub fn get_item_index_at(&self, row: usize) -> Option<usize> {
+        let index = self.offset + row;
+
+        match index < self.items.len() {
+            true => Some(index),
+            false => None,
+        }
+    }
+
+    /// Keeps the selection on the list after its last items were removed.
+    pub fn last_if_past_end(&mut self) {
+        if self.list_state.selected().is_some_and(|index| index >= self.items.len()) {
+            self.last();
+        }
+    }
+
+    pub fn delete_selected_item(&mut self) -> Result<(), String> {
+        match self.list_state.selected() {
+            Some(i) => {
+                self.items.remove(i);
+            }
+            None => {
+                return Err(String::from("Cannot delete item: ITEM WITH THIS INDEX WAS NOT FOUND."));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Popup {
+    fn default() -> Self {
+        Popup {
+            active: false,
+            message: String::new(),
+            kind: PopupKind::Info,
+            confirmation: None,
+            shown_at: None,
+        }
+    }
+}
+
+impl Popup {
+    pub fn activate_popup(&mut self, message: &str, kind: PopupKind) {
+        self.active = true;
+        self.message = message.to_string();
+        self.kind = kind;
+        self.shown_at = Some(Instant::now());
+    }
+
+    /// Shows `message` as a yes/no question, `App::answer_confirmation` runs the
+    /// `confirmation` once it is answered with yes.
+    pub fn ask(&mut self, message: &str, confirmation: Confirmation) {
+        self.activate_popup(message, PopupKind::Info);
+        self.confirmation = Some(confirmation);
+    }
+
+    pub fn deactivate_popup(&mut self) {
+        self.active = false;
+        self.confirmation = None;
+        self.shown_at = None;
+    }
+
+    /// Whether the message has been shown long enough at `now` to close on its
+    /// own. Questions and errors stay until they are answered or dismissed.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        match (self.active, &self.confirmation, self.kind, self.shown_at) {
+            (true, None, PopupKind::Info | PopupKind::Success, Some(shown_at)) =>
+                now.saturating_duration_since(shown_at) >= POPUP_DISMISS_AFTER,
+            _ => false,
+        }
+    }
+}
+
+impl Default for App {
+    fn default() -> App {
+        App::new(CONFIG, &logging::default_log_path(CONFIG).to_string_lossy())
+    }
+}
+
+impl App {
+    pub fn new(config_path: &str, log_path: &str) -> App {
+        App {
+            state: State::Start,
+            previous_state: State::Start,
+            items: StatefulList::with_items(State::Start.create_items(None, config_path).unwrap()),
+            prompts: Vec::new(),
+            input: LineEditor::default(),
+            input_mode: InputMode::Normal,
+            messages: Vec::new(),
+            creation_error: None,
+            reviewing: false,
+            choices: Vec::new(),
+            popup: Popup::default(),
+            current_preset: None,
+            debug_mode: false,
+            config_path: config_path.to_string(),
+            log_path: log_path.to_string(),
+            areas: ScreenAreas::default(),
+            last_click: None,
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            pending_keys: Vec::new(),
+            help_scroll: None,
+            layout_editor: None,
+        }
+    }
+
+    pub fn get_state(&self) -> State {
+        self.state
+    }
+
+    pub fn insert_char(&mut self, ch: char) {
+        if self.reviewing {
+            return;
+        }
+        self.input.insert_char(ch);
+    }
+
+    /// Runs an editing action of the input box. `Submit` and `Cancel` depend on
+    /// the screen and are handled by `run_app`.
+    pub fn handle_text_action(&mut self, action: Action) {
+        if self.reviewing {
+            return;
+        }
+        let input = &mut self.input;
+
+        match action {
+            Action::Left => input.move_left(false),
+            Action::Right => input.move_right(false),
+            Action::WordLeft => input.move_word_left(false),
+            Action::WordRight => input.move_word_right(false),
+            Action::SelectLeft => input.move_left(true),
+            Action::SelectRight => input.move_right(true),
+            Action::Home => input.home(false),
+            Action::End => input.end(false),
+            Action::SelectAll => input.select_all(),
+            Action::Backspace => input.backspace(),
+            Action::DeleteForward => input.delete_forward(),
+            Action::DeleteWordLeft => input.delete_word_left(),
+            Action::DeleteWordRight => input.delete_word_right(),
+            Action::Undo => input.undo(),
+            Action::Redo => input.redo(),
+            Action::Copy => {
+                if let Some(selected) = input.selected_text() {
+                    copy_to_clipboard(selected.to_string());
+                }
+            }
+            Action::Cut => {
+                if let Some(selected) = input.cut() {
+                    copy_to_clipboard(selected);
+                }
+            }
+            Action::Paste => {
+                match cli_clipboard::get_contents() {
+                    // The input box is a single line.
+                    Ok(contents) => input.insert_str(&contents.replace(['\r', '\n'], "")),
+                    Err(err) => error!("Something went wrong while trying to paste: {:?}", err),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks the answer in the input box and adds the questions it leads to.
+    fn create_preset_value(
+        &mut self,
+        pch: &mut PresetCreationHelper,
+        app_config: &AppConfig
+    ) -> Result<(), String> {
+        let msg_length = self.messages.len();
+        match self.info_step() {
+            Some(0) => {
+                return Ok(());
+            }
+            Some(step) => {
+                let shells = App::shell_choices(step);
+                return match shells.contains(&App::format_input(self.input.text())) {
+                    true => Ok(()),
+                    false => Err(format!("Choose one of: {}.", shells.join(", "))),
+                };
+            }
+            None => {}
+        }
+
+        if msg_length == 0 {
+            let name = App::format_input(self.input.text());
+            if name.is_empty() {
+                return Err(String::from("The name cannot be empty."));
+            }
+            if app_config.get_preset_by_name(&name).is_some() {
+                return Err(format!("A preset named '{}' already exists.", name));
+            }
+        } else if msg_length == 1 {
+            let tabs_amount = self.input
+                .text()
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|tabs| (1..=10).contains(tabs))
+                .ok_or_else(|| String::from("Enter a number of tabs from 1 to 10."))?;
+
+            for n in 1..=tabs_amount {
+                self.prompts.push(format!("Enter windows amount (1-4) for tab number {}: ", n));
+            }
+            pch.max_windows = tabs_amount as usize;
+        } else if msg_length > 1 && pch.windows.len() < pch.max_windows {
+            let input = self.input
+                .text()
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|windows| (1..=4).contains(windows))
+                .ok_or_else(|| String::from("Enter a number of windows from 1 to 4."))?;
+            pch.windows.push_back(input);
+
+            for n in 1..=input {
+                self.prompts.push(format!("Enter arg {} for window {}", n, pch.windows.len()));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn format_input(input: &str) -> String {
+        let mut input = input.trim();
+        if input.ends_with(',') {
+            input = &input[..input.len() - 1];
+        }
+
+        input.to_string()
+    }
+
+    /// Takes the answer of the current question. After the last one the preset is
+    /// shown for review, and submitting the review writes it to the config.
+    pub fn handle_creating_preset(
+        &mut self,
+        pch: &mut PresetCreationHelper,
+        app_config: &mut AppConfig
+    ) {
+        if self.reviewing {
+            let preset = self.created_preset();
+            app_config.add_presets(vec![preset.clone()]);
+            // Kept on review, so the preset can be written again once the file is fixed.
+            if !self.save_config(app_config) {
+                let _ = app_config.delete_preset_by_name(&preset.name);
+                return;
+            }
+            pch.reset();
+
+            self.popup.activate_popup("Preset created successfuly :)", PopupKind::Success);
+
+            self.handle_state_change(("", self.previous_state), None);
+            return;
+        }
+
+        if let Err(err) = self.create_preset_value(pch, app_config) {
+            self.creation_error = Some(err);
+            return;
+        }
+        self.creation_error = None;
+
+        let input = Self::format_input(&self.input.take());
+
+        self.messages.push(input);
+
+        if self.messages.len() == self.prompts.len() {
+            match self.info_step_count() {
+                0 => {
+                    self.prompts.extend(INFO_PROMPTS.iter().map(|prompt| prompt.to_string()));
+                }
+                _ => {
+                    self.reviewing = true;
+                }
+            }
+        }
+
+        self.update_choices();
+        let defaults = &app_config.settings.preset_defaults;
+        match self.info_step() {
+            Some(0) => self.input.set_text(&defaults.wt_profile),
+            Some(1) => self.input.set_text(&defaults.init_shell.as_string()),
+            Some(2) => self.input.set_text(&defaults.target_shell.as_string()),
+            _ => {}
+        }
+    }
+
+    /// Index of the current question among `INFO_PROMPTS`, `None` while the tabs,
+    /// windows and commands are asked.
+    fn info_step(&self) -> Option<usize> {
+        let prompt = self.prompts.get(self.messages.len())?;
+        INFO_PROMPTS.iter().position(|info| info == prompt)
+    }
+
+    fn info_step_count(&self) -> usize {
+        self.prompts
+            .iter()
+            .filter(|prompt| INFO_PROMPTS.contains(&prompt.as_str()))
+            .count()
+    }
+
+    fn update_choices(&mut self) {
+        self.choices = match self.info_step() {
+            Some(0) => {
+                let mut profiles = doctor::read_wt_profiles().unwrap_or_default();
+                profiles.insert(0, String::new());
+                profiles
+            }
+            Some(step) => App::shell_choices(step),
+            None => Vec::new(),
+        };
+    }
+
+    /// The shells offered at the init (1) or target (2) shell question.
+    fn shell_choices(step: usize) -> Vec<String> {
+        match step {
+            1 => ShellType::init_names(),
+            _ => ShellType::names(),
+        }
+    }
+
+    /// Puts the next (`forward`) or previous choice of the current question in the input box.
+    pub fn cycle_choice(&mut self, forward: bool) {
+        if self.choices.is_empty() || self.reviewing {
+            return;
+        }
+
+        let len = self.choices.len();
+        let next = match self.choices.iter().position(|choice| choice == self.input.text()) {
+            Some(index) if forward => (index + 1) % len,
+            Some(index) => (index + len - 1) % len,
+            None => 0,
+        };
+        self.input.set_text(&self.choices[next]);
+    }
+
+    /// The preset described by the answers of the wizard.
+    pub fn created_preset(&self) -> Preset {
+        let info_answers = match self.info_step_count() {
+            0 => 0,
+            _ => self.messages.len().saturating_sub(self.prompts.len() - INFO_PROMPTS.len()),
+        };
+        let (structure, info) = self.messages.split_at(self.messages.len() - info_answers);
+
+        let mut preset = Preset::from_input(structure);
+        if let Some(profile) = info.first() {
+            preset.preset_info.wt_profile = profile.to_string();
+        }
+        if let Some(Ok(shell)) = info.get(1).map(|shell| shell.parse::<ShellType>()) {
+            preset.preset_info.init_shell = shell;
+        }
+        if let Some(Ok(shell)) = info.get(2).map(|shell| shell.parse::<ShellType>()) {
+            preset.preset_info.target_shell = shell;
+        }
+        preset
+    }
+
+    /// Goes back to the previous question with its answer in the input box and
+    /// drops the questions that answer added.
+    pub fn step_back_preset_creation(&mut self, pch: &mut PresetCreationHelper) {
+        self.reviewing = false;
+        self.creation_error = None;
+
+        let Some(answer) = self.messages.pop() else {
+            return;
+        };
+        let step = self.messages.len();
+        let info_prompts = self.info_step_count();
+
+        if info_prompts > 0 && step < self.prompts.len() - info_prompts {
+            self.prompts.truncate(self.prompts.len() - info_prompts);
+        } else if step == 1 {
+            self.prompts.truncate(2);
+            pch.reset();
+        } else if step > 1 && step < 2 + pch.max_windows {
+            let windows = pch.windows.pop_back().unwrap_or(0) as usize;
+            self.prompts.truncate(self.prompts.len() - windows);
+        }
+
+        self.input.set_text(&answer);
+        self.update_choices();
+    }
+
+    /// Asks before moving the selected preset to the trash, or before removing the
+    /// selected trashed preset for good.
+    pub fn handle_deleting_preset(&mut self, app_config: &AppConfig) {
+        if self.popup.active {
+            return;
+        }
+        let Some(index) = self.items.get_selected_item_index() else {
+            return;
+        };
+
+        match self.state {
+            State::ChoosePreset => {
+                let Some(item) = self.items.get_selected_item() else {
+                    return;
+                };
+                let message = match app_config.settings.trash_days {
+                    0 => format!("Move '{}' to the trash? (y/n)", item.name),
+                    days =>
+                        format!(
+                            "Move '{}' to the trash? It is deleted for good after {} days. (y/n)",
+                            item.name,
+                            days
+                        ),
+                };
+                self.popup.ask(&message, Confirmation::DeletePreset(item.name));
+            }
+            State::Trash => {
+                let Some(trashed) = app_config.trash.get(index) else {
+                    return;
+                };
+                let message = format!(
+                    "Delete '{}' for good? This cannot be undone. (y/n)",
+                    trashed.preset.name
+                );
+                self.popup.ask(&message, Confirmation::PurgePreset(index));
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes `app_config` to the config file. A failure is shown in an error popup
+    /// and the change stays in memory, to be written by the next save.
+    fn save_config(&mut self, app_config: &AppConfig) -> bool {
+        match app_config.save(&self.config_path) {
+            Ok(()) => true,
+            Err(err) => {
+                error!("{}", err);
+                self.popup.activate_popup(&err, PopupKind::Error);
+                false
+            }
+        }
+    }
+
+    /// Runs the action of the confirmation popup when `yes`, closes the popup either way.
+    pub fn answer_confirmation(&mut self, yes: bool, app_config: &mut AppConfig) {
+        let Some(confirmation) = self.popup.confirmation.take() else {
+            return;
+        };
+        self.popup.deactivate_popup();
+
+        if !yes {
+            return;
+        }
+
+        let result = match &confirmation {
+            Confirmation::LaunchPreset(_) => {
+                self.handle_state_change(("", State::RunConfig), Some(app_config));
+                return;
+            }
+            Confirmation::DeletePreset(name) => app_config.trash_preset_by_name(name, SystemTime::now()),
+            Confirmation::PurgePreset(index) => app_config.purge_preset(*index),
+        };
+        if let Err(err) = result {
+            error!("{}", err);
+            self.popup.activate_popup(&err, PopupKind::Error);
+            return;
+        }
+        if let Err(err) = self.items.delete_selected_item() {
+            error!("{}", err);
+            return;
+        }
+        self.save_config(app_config);
+
+        if self.items.items.is_empty() {
+            self.handle_state_change(("", self.previous_state), Some(app_config));
+        } else {
+            self.items.last_if_past_end();
+        }
+    }
+
+    /// Moves the selected trashed preset back to the presets.
+    pub fn restore_preset(&mut self, app_config: &mut AppConfig) {
+        if self.state != State::Trash {
+            return;
+        }
+        let Some(index) = self.items.get_selected_item_index() else {
+            return;
+        };
+
+        match app_config.restore_preset(index) {
+            Ok(name) => {
+                let saved = self.save_config(app_config);
+                let _ = self.items.delete_selected_item();
+
+                if self.items.items.is_empty() {
+                    self.handle_state_change(("", self.previous_state), Some(app_config));
+                } else {
+                    self.items.last_if_past_end();
+                }
+                if saved {
+                    self.popup.activate_popup(&format!("Restored '{}'.", name), PopupKind::Success);
+                }
+            }
+            Err(err) => {
+                error!("{}", err);
+                self.popup.activate_popup(&err, PopupKind::Error);
+            }
+        }
+    }
+
+    /// Validates the input with the field being edited and stores it in the
+    /// preset or the settings. A rejected input keeps the field open with the
+    /// reason shown next to the input box. Choices and toggles are stored as
+    /// picked in the dropdown.
+    pub fn handle_editing_preset(&mut self, app_config: &mut AppConfig) {
+        let Some(index) = self.items.get_selected_item_index() else {
+            return;
+        };
+        let Some(field) = self.items.items.get_mut(index).and_then(|item| item.field.as_mut()) else {
+            return;
+        };
+
+        if !field.get_value().is_selectable() {
+            let input = App::format_input(self.input.text());
+            if field.submit(&input).is_err() {
+                return;
+            }
+        }
+
+        if self.store_field(index, app_config) {
+            self.handle_state_change(("", self.previous_state), Some(app_config));
+        }
+    }
+
+    /// Applies the field of the item at `index` to the edited preset, or to the
+    /// settings outside Edit Preset, and writes the config. Returns false when
+    /// the field was rejected, the reason is kept on the field.
+    fn store_field(&mut self, index: usize, app_config: &mut AppConfig) -> bool {
+        let Some(field) = self.items.items.get_mut(index).and_then(|item| item.field.as_mut()) else {
+            return false;
+        };
+
+        let result = match &self.current_preset {
+            Some(current) => {
+                let name = field.get_value().to_string();
+                let taken = field.get_target() == FieldTarget::Name &&
+                    name != current.name &&
+                    app_config.get_preset_by_name(&name).is_some();
+                match (taken, app_config.get_mut_preset_by_name(&current.name)) {
+                    (true, _) => Err(format!("A preset named '{}' already exists.", name)),
+                    (false, Some(preset)) =>
+                        preset.apply_field(field).map(|_| {
+                            self.current_preset = Some(preset.clone());
+                        }),
+                    (false, None) => {
+                        return false;
+                    }
+                }
+            }
+            None => app_config.settings.apply_field(field),
+        };
+        if let Err(err) = result {
+            error!("{}", err);
+            field.reject(&err);
+            return false;
+        }
+
+        self.save_config(app_config);
+        self.debug_mode = app_config.settings.debug_mode;
+        if let Ok(theme) = Theme::from_settings(&app_config.settings.theme) {
+            self.theme = theme;
+        }
+        true
+    }
+
+    /// Moves the field edited in the dropdown to its next (`forward`) or previous value.
+    pub fn cycle_field(&mut self, forward: bool) {
+        let Some(index) = self.items.get_selected_item_index() else {
+            return;
+        };
+        if let Some(field) = self.items.items.get_mut(index).and_then(|item| item.field.as_mut()) {
+            field.cycle(forward);
+        }
+    }
+
+    /// Cycles the choice or flips the toggle selected in the list and saves it
+    /// right away. Other fields are left alone.
+    pub fn toggle_field(&mut self, app_config: &mut AppConfig) {
+        let Some(index) = self.items.get_selected_item_index() else {
+            return;
+        };
+        let Some(field) = self.items.items.get_mut(index).and_then(|item| item.field.as_mut()) else {
+            return;
+        };
+        if !field.get_value().is_selectable() {
+            return;
+        }
+        field.cycle(true);
+
+        if !self.store_field(index, app_config) {
+            let error = self.field_error().unwrap_or_default();
+            self.popup.activate_popup(&error, PopupKind::Error);
+            return;
+        }
+        let item = &mut self.items.items[index];
+        if let Some(field) = &item.field {
+            item.name = field.item_name();
+        }
+    }
+
+    /// What the dropdown of the field being picked shows, with the current option.
+    pub fn field_options(&self) -> Option<(Vec<String>, usize)> {
+        self.items
+            .get_selected_item()
+            .and_then(|item| item.field)
+            .and_then(|field| field.get_value().options())
+    }
+
+    /// Why the input of the field being edited was rejected.
+    pub fn field_error(&self) -> Option<String> {
+        self.items
+            .get_selected_item()
+            .and_then(|item| item.field)
+            .and_then(|field| field.get_error().map(String::from))
+    }
+
+    /// Applies `edit` to the tab or window selected in Edit Preset and saves the
+    /// preset. The selection follows the tab or window that was changed.
+    pub fn edit_structure(&mut self, edit: StructureEdit, app_config: &mut AppConfig) {
+        if self.state != State::EditPreset || self.popup.active {
+            return;
+        }
+        let Some(item) = self.items.get_selected_item() else {
+            return;
+        };
+        let Some(current) = &self.current_preset else {
+            return;
+        };
+        let Some(preset) = app_config.get_mut_preset_by_name(&current.name) else {
+            return;
+        };
+
+        let result = match item.field.map(|field| field.get_target()) {
+            Some(FieldTarget::Windows(tab)) =>
+                preset.edit_tab(tab, edit).map(FieldTarget::Windows),
+            Some(FieldTarget::Args(arg)) =>
+                preset.edit_window(arg, edit).map(FieldTarget::Args),
+            _ => Err(String::from("Select a tab or a window to change the layout.")),
+        };
+        let selected = match result {
+            Ok(selected) => selected,
+            Err(err) => {
+                self.popup.activate_popup(&err, PopupKind::Error);
+                return;
+            }
+        };
+
+        self.current_preset = Some(preset.clone());
+        self.items.items = preset.into_items();
+        self.select_structure_item(selected);
+
+        self.save_config(app_config);
+    }
+
+    /// Selects the Edit Preset line of the tab or window `target` points at.
+    fn select_structure_item(&mut self, target: FieldTarget) {
+        let index = self.items.items
+            .iter()
+            .position(|item| item.field.as_ref().map(FormField::get_target) == Some(target));
+        if index.is_some() {
+            self.items.list_state.select(index);
+        }
+    }
+
+    /// Opens the layout editor for the tab of the line selected in Edit Preset.
+    pub fn open_layout_editor(&mut self, app_config: &AppConfig) {
+        if self.state != State::EditPreset || self.popup.active {
+            return;
+        }
+        let Some(preset) = self.current_preset.clone() else {
+            return;
+        };
+        let target = self.items
+            .get_selected_item()
+            .and_then(|item| item.field)
+            .map(|field| field.get_target());
+        let tab = match target {
+            Some(FieldTarget::Windows(tab)) => Some(tab),
+            Some(FieldTarget::Args(arg)) => preset.tab_of_arg(arg),
+            _ => None,
+        };
+        let Some(tab) = tab else {
+            self.popup.activate_popup("Select a tab or a window to arrange the tab.", PopupKind::Info);
+            return;
+        };
+
+        let args = preset.get_tab_args(tab).unwrap_or(&[]).to_vec();
+        self.layout_editor = Some(LayoutEditor::new(tab, preset.layout(tab), args));
+        self.handle_state_change(("", State::LayoutEditor), Some(app_config));
+    }
+
+    /// Splits, resizes, focuses or deletes panes in the layout editor. `Launch`
+    /// saves the layout to the preset, `Back` leaves without saving.
+    pub fn handle_layout_action(&mut self, action: Action, app_config: &mut AppConfig) {
+        if self.popup.active {
+            if let Action::Back | Action::Launch = action {
+                self.popup.deactivate_popup();
+            }
+            return;
+        }
+        let Some(editor) = self.layout_editor.as_mut() else {
+            return;
+        };
+
+        let result = match action {
+            Action::SplitVertical => editor.split(SplitDirection::Vertical),
+            Action::SplitHorizontal => editor.split(SplitDirection::Horizontal),
+            Action::Delete => editor.delete(),
+            Action::Grow => {
+                editor.resize(LAYOUT_RESIZE_STEP);
+                Ok(())
+            }
+            Action::Shrink => {
+                editor.resize(-LAYOUT_RESIZE_STEP);
+                Ok(())
+            }
+            Action::Left => {
+                editor.move_focus(FocusDirection::Left);
+                Ok(())
+            }
+            Action::Right => {
+                editor.move_focus(FocusDirection::Right);
+                Ok(())
+            }
+            Action::Up => {
+                editor.move_focus(FocusDirection::Up);
+                Ok(())
+            }
+            Action::Down => {
+                editor.move_focus(FocusDirection::Down);
+                Ok(())
+            }
+            Action::Launch => {
+                return self.save_layout(app_config);
+            }
+            Action::Back => {
+                let tab = editor.get_tab();
+                self.handle_state_change(("", State::EditPreset), Some(app_config));
+                self.select_structure_item(FieldTarget::Windows(tab));
+                return;
+            }
+            _ => Ok(()),
+        };
+
+        if let Err(err) = result {
+            self.popup.activate_popup(&err, PopupKind::Error);
+        }
+    }
+
+    fn save_layout(&mut self, app_config: &mut AppConfig) {
+        let Some(editor) = self.layout_editor.take() else {
+            return;
+        };
+        let Some(current) = &self.current_preset else {
+            return;
+        };
+        let Some(preset) = app_config.get_mut_preset_by_name(&current.name) else {
+            return;
+        };
+
+        if let Err(err) = preset.apply_layout(&editor) {
+            error!("{}", err);
+            self.layout_editor = Some(editor);
+            self.popup.activate_popup(&err, PopupKind::Error);
+            return;
+        }
+        self.current_preset = Some(preset.clone());
+
+        let saved = self.save_config(app_config);
+
+        self.handle_state_change(("", State::EditPreset), Some(app_config));
+        self.select_structure_item(FieldTarget::Windows(editor.get_tab()));
+        if saved {
+            self.popup.activate_popup("Layout saved.", PopupKind::Success);
+        }
+    }
+
+    pub fn cancel_preset_creation(&mut self, pch: &mut PresetCreationHelper) {
+        if self.popup.active {
+            self.popup.deactivate_popup();
+            return;
+        }
+        pch.reset();
+        self.handle_state_change(("", State::Start), None);
+    }
+
+    pub fn go_back(&mut self, app_config: &AppConfig) {
+        if self.popup.active {
+            self.popup.deactivate_popup();
+            return;
+        }
+
+        self.handle_state_change(("", self.previous_state), Some(app_config));
+    }
+
+    pub fn edit_preset(&mut self, app_config: &mut AppConfig) {
+        let Some(item) = self.items.get_selected_item() else {
+            return;
+        };
+
+        if item.leading_state != State::RunConfig {
+            return;
+        }
+
+        let Some(preset) = app_config.get_mut_preset_by_name(&item.name) else {
+            return;
+        };
+
+        self.current_preset = Some(preset.clone());
+        self.handle_state_change(("", State::EditPreset), Some(app_config));
+    }
+    /// Opens the filter box on ChoosePreset, the typed text lives in `input`.
+    pub fn start_filter(&mut self) {
+        if self.state != State::ChoosePreset || self.popup.active {
+            return;
+        }
+
+        self.input_mode = InputMode::Filter;
+        self.input.clear();
+    }
+
+    /// Lists the presets matching `input`, best match first and selected.
+    pub fn apply_filter(&mut self, app_config: &AppConfig) {
+        let mut matches = app_config.presets
+            .iter()
+            .filter_map(|preset| preset.fuzzy_match(self.input.text()).map(|matched| (preset, matched)))
+            .collect::<Vec<(&Preset, FuzzyMatch)>>();
+        matches.sort_by_key(|(_, matched)| std::cmp::Reverse(matched.score));
+
+        self.items.items = matches
+            .into_iter()
+            .map(|(preset, matched)| {
+                let mut item = Item::new(preset.name.to_string(), State::RunConfig, None);
+                item.matched_indices = matched.indices;
+                item
+            })
+            .collect();
+
+        let index = match self.items.items.is_empty() {
+            true => None,
+            false => Some(0),
+        };
+        self.items.list_state.select(index);
+    }
+
+    /// Closes the filter box and shows every preset again, keeping the selection.
+    pub fn clear_filter(&mut self, app_config: &AppConfig) {
+        let selected = self.items.get_selected_item();
+
+        self.input_mode = InputMode::Normal;
+        self.input.clear();
+        self.apply_filter(app_config);
+
+        if let Some(selected) = selected {
+            let index = self.items.items.iter().position(|item| item.name == selected.name);
+            self.items.list_state.select(index.or(Some(0)));
+        }
+    }
+
+    pub fn open_help(&mut self) {
+        self.help_scroll = Some(0);
+    }
+
+    /// Scrolls or closes the help overlay. `ui` clamps the scroll offset to the
+    /// length of the help text.
+    pub fn handle_help_action(&mut self, action: Action) {
+        let Some(scroll) = self.help_scroll else {
+            return;
+        };
+
+        match action {
+            Action::Up => {
+                self.help_scroll = Some(scroll.saturating_sub(1));
+            }
+            Action::Down => {
+                self.help_scroll = Some(scroll.saturating_add(1));
+            }
+            Action::First => {
+                self.help_scroll = Some(0);
+            }
+            Action::Last => {
+                self.help_scroll = Some(u16::MAX);
+            }
+            Action::Back | Action::Cancel | Action::Help | Action::Quit => {
+                self.help_scroll = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Runs the timers, called on every `AppEvent::Tick`.
+    pub fn handle_tick(&mut self, now: Instant) {
+        if self.popup.is_expired(now) {
+            self.popup.deactivate_popup();
+        }
+    }
+
+    pub fn handle_message(&mut self, message: AppMessage, app_config: &mut AppConfig) {
+        match message {
+            AppMessage::ConfigChanged => {
+                // Saving from the app changes the file too, that one is already loaded.
+                let config = match AppConfig::load(&self.config_path) {
+                    Ok(config) if config != *app_config => config,
+                    Ok(_) => {
+                        return;
+                    }
+                    Err(err) => {
+                        error!("{}", err);
+                        return;
+                    }
+                };
+                *app_config = config;
+                self.refresh_items(app_config);
+                if !self.popup.active {
+                    self.popup.activate_popup(
+                        "The config was changed outside the app and reloaded.",
+                        PopupKind::Info
+                    );
+                }
+            }
+            AppMessage::Launched { name, result: Ok(()) } => {
+                self.popup.activate_popup(&format!("Launched '{}'.", name), PopupKind::Success);
+            }
+            AppMessage::Launched { result: Err(err), .. } => {
+                self.popup.activate_popup(&err, PopupKind::Error);
+            }
+        }
+    }
+
+    /// Rebuilds the list of the menu shown from `app_config`, keeping the
+    /// selected line. Screens in the middle of an edit are left alone.
+    pub fn refresh_items(&mut self, app_config: &AppConfig) {
+        if !matches!(self.input_mode, InputMode::Normal) {
+            return;
+        }
+        let state = self.state;
+        if !matches!(state, State::Start | State::ChoosePreset | State::Settings | State::Trash) {
+            return;
+        }
+        let selected = self.items.get_selected_item_index();
+
+        // Entering the state it is already in is skipped, so leave it first.
+        self.state = State::RunConfig;
+        self.handle_state_change(("", state), Some(app_config));
+
+        if let (Some(index), false) = (selected, self.items.items.is_empty()) {
+            self.items.list_state.select(Some(index.min(self.items.items.len() - 1)));
+        }
+    }
+
+    pub fn handle_mouse_event(&mut self, mouse: MouseEvent, app_config: &AppConfig) {
+        let (column, row) = (mouse.column, mouse.row);
+
+        if self.help_scroll.is_some() {
+            match mouse.kind {
+                MouseEventKind::ScrollDown => self.handle_help_action(Action::Down),
+                MouseEventKind::ScrollUp => self.handle_help_action(Action::Up),
+                MouseEventKind::Down(_) => self.handle_help_action(Action::Back),
+                _ => {}
+            }
+            return;
+        }
+
+        if self.popup.active {
+            let outside_popup = !self.areas.popup.is_some_and(|area| contains(area, column, row));
+            if let (MouseEventKind::Down(_), true) = (mouse.kind, outside_popup) {
+                self.popup.deactivate_popup();
+            }
+            return;
+        }
+
+        // While creating a preset the main area shows prompts instead of `items`.
+        let showing_prompts = matches!(self.input_mode, InputMode::Input);
+
+        // The dropdown of a choice scrolls through its options, the list stays put.
+        if let InputMode::Select = self.input_mode {
+            match mouse.kind {
+                MouseEventKind::ScrollDown => self.cycle_field(true),
+                MouseEventKind::ScrollUp => self.cycle_field(false),
+                _ => {}
+            }
+            return;
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollDown if !showing_prompts => self.items.next(),
+            MouseEventKind::ScrollUp if !showing_prompts => self.items.previous(),
+            MouseEventKind::Down(MouseButton::Left) if contains(self.areas.input, column, row) => {
+                if let InputMode::Normal = self.input_mode {
+                    return;
+                }
+                let offset = column.saturating_sub(self.areas.input.x + 1) as usize;
+
+                self.input.click(offset);
+            }
+            MouseEventKind::Down(MouseButton::Left) if
+                !showing_prompts && contains(self.areas.list, column, row)
+            => {
+                let Some(list_row) = row.checked_sub(self.areas.list.y + 1) else {
+                    return;
+                };
+                let Some(index) = self.items.get_item_index_at(list_row as usize) else {
+                    return;
+                };
+
+                let double_click = matches!(
+                    self.last_click,
+                    Some((time, last_index)) if last_index == index && time.elapsed() <= DOUBLE_CLICK_TIME
+                );
+
+                self.items.list_state.select(Some(index));
+
+                if double_click {
+                    self.last_click = None;
+                    self.choose_item(app_config);
+                } else {
+                    self.last_click = Some((Instant::now(), index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn choose_item(&mut self, app_config: &AppConfig) {
+        if self.popup.active {
+            self.popup.deactivate_popup();
+        }
+        let Some(item) = self.items.get_selected_item() else {
+            return;
+        };
+
+        if item.leading_state == State::RunConfig && app_config.settings.confirm_before_launch {
+            let message = format!("Launch '{}'? (y/n)", item.name);
+            self.popup.ask(&message, Confirmation::LaunchPreset(item.name));
+            return;
+        }
+
+        self.handle_state_change((item.name.as_str(), item.leading_state), Some(app_config));
+    }
+
+    pub fn handle_state_change(
+        &mut self,
+        (_, new_state): (&str, State),
+        app_config: Option<&AppConfig>
+    ) {
+        if new_state == self.state {
+            return;
+        }
+
+        self.previous_state = match new_state {
+            State::EditPreset => State::ChoosePreset,
+            State::ChangeFieldName => self.get_state(),
+            State::Trash => State::Settings,
+            State::LayoutEditor => State::EditPreset,
+            _ => State::Start,
+        };
+        self.state = new_state;
+
+        self.input.clear();
+        self.messages.clear();
+        self.creation_error = None;
+        self.reviewing = false;
+        self.choices.clear();
+
+        match new_state {
+            State::CreatePreset => {
+                self.items.items.clear();
+                let new_prompts = self.state.create_prompts().unwrap();
+
+                for prompt in new_prompts {
+                    self.prompts.push(prompt);
+                }
+                self.input_mode = InputMode::Input;
+                self.items.list_state.select(Some(0));
+            }
+            State::ChoosePreset => {
+                self.items.items.clear();
+                self.input_mode = InputMode::Normal;
+                let Some(config) = app_config else {
+                    return;
+                };
+
+                match &config.presets {
+                    presets if !presets.is_empty() => {
+                        for preset in presets {
+                            let item = Item::new(preset.name.to_string(), State::RunConfig, None);
+                            self.items.items.push(item);
+                        }
+                    }
+                    _ => {
+                        self.handle_state_change(("", State::Start), None);
+                        self.popup.activate_popup("No presets created.", PopupKind::Error);
+                    }
+                }
+                self.items.list_state.select(Some(0));
+            }
+            State::EditPreset => {
+                self.input_mode = InputMode::Normal;
+                self.layout_editor = None;
+                self.items.items.clear();
+                match app_config {
+                    Some(config) if !config.presets.is_empty() => {
+                        let mut new_items = self.current_preset.clone().unwrap().into_items();
+
+                        self.items.items.append(&mut new_items);
+                    }
+                    _ => {}
+                }
+            }
+            State::ChangeFieldName => {
+                self.input_mode = InputMode::Edit;
+                let Some(index) = self.items.get_selected_item_index() else {
+                    return;
+                };
+                let Some(field) = self.items.items
+                    .get_mut(index)
+                    .and_then(|item| item.field.as_mut()) else {
+                    return;
+                };
+
+                offer_wt_profiles(field);
+                match field.get_value().is_selectable() {
+                    true => {
+                        self.input_mode = InputMode::Select;
+                    }
+                    false => self.input.set_text(&field.get_value().to_string()),
+                }
+            }
+            State::RunConfig => {}
+            State::LayoutEditor => {
+                self.items.items.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            State::Trash => {
+                self.items.items.clear();
+                self.input_mode = InputMode::Normal;
+                let Some(config) = app_config else {
+                    return;
+                };
+
+                if config.trash.is_empty() {
+                    self.handle_state_change(("", State::Settings), app_config);
+                    self.popup.activate_popup("The trash is empty.", PopupKind::Info);
+                    return;
+                }
+
+                let now = SystemTime::now();
+                for trashed in &config.trash {
+                    let name = format!(
+                        "{} (deleted {} days ago)",
+                        trashed.preset.name,
+                        trashed.days_since_deleted(now)
+                    );
+                    self.items.items.push(Item::new(name, State::Trash, None));
+                }
+                self.items.list_state.select(Some(0));
+            }
+            State::Doctor => {
+                self.items.items.clear();
+                self.input_mode = InputMode::Normal;
+
+                for check in doctor::run_checks(&self.config_path, &self.log_path) {
+                    for line in check.to_lines() {
+                        self.items.items.push(Item::new(line, State::Doctor, None));
+                    }
+                }
+                self.items.list_state.select(Some(0));
+            }
+            _ => {
+                self.items.items.clear();
+                self.prompts.clear();
+                self.current_preset = None;
+                self.input_mode = InputMode::Normal;
+                let new_items = self.state.create_items(app_config, &self.config_path).unwrap();
+
+                for item in new_items {
+                    self.items.items.push(item);
+                }
+                self.items.list_state.select(Some(0));
+                if let Some(config) = app_config {
+                    self.debug_mode = config.settings.debug_mode;
+                }
+            }
+        }
+    }
+}
+
+/// Turns a wt profile field into a choice of the profiles found in the Windows
+/// Terminal settings. The current name stays one of the options, so a profile
+/// that was removed from the terminal is not lost by opening the field.
+fn offer_wt_profiles(field: &mut FormField) {
+    if !matches!(field.get_target(), FieldTarget::WtProfile | FieldTarget::DefaultWtProfile) {
+        return;
+    }
+    let Some(mut profiles) = doctor::read_wt_profiles() else {
+        return;
+    };
+
+    let current = field.get_value().to_string();
+    profiles.insert(0, String::new());
+    if !profiles.contains(&current) {
+        profiles.push(current.clone());
+    }
+    *field = FormField::new(
+        field.get_target(),
+        field.get_label(),
+        FieldValue::choice(profiles, &current)
+    );
+}
+
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
+/// How long an info or success message stays before it closes on its own.
+const POPUP_DISMISS_AFTER: Duration = Duration::from_secs(4);
+
+/// Percent a split moves per `Grow`/`Shrink` in the layout editor.
+const LAYOUT_RESIZE_STEP: i16 = 5;
+
+/// Questions the Create Preset wizard asks after the commands, in this order.
+const INFO_PROMPTS: [&str; 3] = [
+    "Choose the Windows Terminal profile (empty for the default profile):",
+    "Choose the init shell, the one that runs wt.exe:",
+    "Choose the target shell, the one opened in every window:",
+];
+
+fn copy_to_clipboard(text: String) {
+    if let Err(err) = cli_clipboard::set_contents(text) {
+        error!("Something went wrong while trying to copy: {:?}", err);
+    }
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.right() && row >= area.y && row < area.bottom()
+}
+
+impl Default for PresetCreationHelper {
+    fn default() -> Self {
+        PresetCreationHelper::new()
+    }
+}
+
+impl PresetCreationHelper {
+    pub fn new() -> Self {
+        PresetCreationHelper {
+            windows: VecDeque::new(),
+            max_windows: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.windows.clear();
+        self.max_windows = 0;
+    }
+}
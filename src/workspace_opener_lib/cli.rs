@@ -0,0 +1,448 @@
+use super::{
+    completions::CompletionShell,
+    doctor::{ self, Check, CheckStatus },
+    form::FieldTarget,
+    logging::LogOptions,
+    model::{ AppConfig, LaunchPlan, LogLevel, Preset, WriteType, CONFIG },
+};
+use clap::{ Args, Parser, Subcommand, ValueEnum };
+use serde::Serialize;
+use std::{ io::Write, time::SystemTime };
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "workspace_opener",
+    version,
+    about = "Create and run Windows Terminal presets. Starts the TUI when no command is given."
+)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Args, Debug)]
+pub struct GlobalArgs {
+    /// Path to the config file.
+    #[arg(long, global = true, default_value = CONFIG)]
+    pub config: String,
+
+    /// Output format of `list`, `show`, `run --dry-run` and `doctor`.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Log file, overrides `settings.log.path` [default: state/output.log next to the config].
+    #[arg(long, global = true)]
+    pub log_file: Option<String>,
+
+    /// Log level (off, error, warn, info, debug, trace), overrides `settings.log.level`.
+    #[arg(long, global = true)]
+    pub log_level: Option<LogLevel>,
+}
+
+impl GlobalArgs {
+    /// Log options from the config's settings with the command line flags applied.
+    /// A config that cannot be loaded falls back to the default settings.
+    pub fn log_options(&self) -> LogOptions {
+        let settings = AppConfig::load(&self.config)
+            .map(|app_config| app_config.settings)
+            .unwrap_or_default();
+
+        LogOptions::resolve(&settings, &self.config, self.log_file.as_deref(), self.log_level)
+    }
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// List the names of all presets.
+    List,
+    /// Show every field of a preset.
+    Show {
+        name: String,
+    },
+    /// Open a preset in Windows Terminal.
+    Run {
+        name: String,
+        /// Print the launch plan instead of opening it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Create a new preset.
+    Create {
+        #[arg(long)]
+        name: String,
+        /// Amount of tabs (1-10).
+        #[arg(long)]
+        tabs: u8,
+        /// Amount of windows (1-4) for every tab, e.g. `--windows 2,1,1`.
+        #[arg(long, value_delimiter = ',', required = true)]
+        windows: Vec<u8>,
+        /// Commands for one window, separated by commas. Repeat once per window.
+        #[arg(long = "cmd")]
+        cmds: Vec<String>,
+        /// Tag used by the TUI search. Repeat for more tags.
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Move a preset to the trash, like deleting it in the TUI.
+    Delete {
+        name: String,
+        /// Delete the preset for good instead of moving it to the trash.
+        #[arg(long)]
+        permanent: bool,
+    },
+    /// Change a single field of a preset.
+    ///
+    /// Fields: name, tabs, tab.<N>.windows, tab.<N>.window.<M>, wt_profile, init_shell, target_shell,
+    /// tags (comma separated).
+    Set {
+        name: String,
+        field: String,
+        value: String,
+    },
+    /// Check that presets can be opened on this machine.
+    Doctor,
+    /// Print a shell completion script.
+    ///
+    /// e.g. `workspace_opener completions bash > /etc/bash_completion.d/workspace_opener` or
+    /// `workspace_opener completions powershell | Out-String | Invoke-Expression` in $PROFILE.
+    Completions {
+        shell: CompletionShell,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// A preset as stored in the config, plus the problems found by `Preset::validate`.
+#[derive(Serialize)]
+struct PresetReport<'a> {
+    #[serde(flatten)]
+    preset: &'a Preset,
+    problems: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PresetList<'a> {
+    presets: Vec<PresetReport<'a>>,
+}
+
+#[derive(Serialize)]
+struct DoctorReport {
+    ok: bool,
+    checks: Vec<Check>,
+}
+
+#[derive(Serialize)]
+struct RunReport<'a> {
+    preset: &'a str,
+    problems: Vec<String>,
+    plan: Option<LaunchPlan>,
+}
+
+impl<'a> PresetReport<'a> {
+    fn new(preset: &'a Preset) -> Self {
+        PresetReport {
+            preset,
+            problems: preset.validate(),
+        }
+    }
+}
+
+pub fn execute(command: Command, global: &GlobalArgs, out: &mut impl Write) -> Result<(), String> {
+    let (config_path, format) = (global.config.as_str(), global.format);
+
+    match command {
+        Command::Completions { shell } => {
+            return write!(out, "{}", shell.script()).map_err(|err| err.to_string());
+        }
+        Command::Doctor => {
+            let log_path = global.log_options().get_path().to_string_lossy().to_string();
+            return run_doctor(config_path, &log_path, format, out);
+        }
+        _ => {}
+    }
+
+    let mut app_config = AppConfig::load(config_path)?;
+
+    match command {
+        Command::List => {
+            match format {
+                OutputFormat::Text => {
+                    for preset in &app_config.presets {
+                        writeln!(out, "{}", preset.name).map_err(|err| err.to_string())?;
+                    }
+                }
+                OutputFormat::Json => {
+                    let list = PresetList {
+                        presets: app_config.presets.iter().map(PresetReport::new).collect(),
+                    };
+                    write_json(out, &list)?;
+                }
+            }
+        }
+        Command::Show { name } => {
+            let Some(preset) = app_config.get_preset_by_name(&name) else {
+                return Err(not_found("show", &name));
+            };
+
+            match format {
+                OutputFormat::Text => {
+                    // The fields follow the windows, a broken preset only gets its problems.
+                    let problems = preset.validate();
+                    for problem in &problems {
+                        writeln!(out, "Problem: {}", problem).map_err(|err| err.to_string())?;
+                    }
+                    if problems.is_empty() {
+                        for field in preset.form_fields() {
+                            writeln!(out, "{}", field.item_name()).map_err(|err| err.to_string())?;
+                        }
+                    }
+                }
+                OutputFormat::Json => write_json(out, &PresetReport::new(preset))?,
+            }
+        }
+        Command::Run { name, dry_run: true } => {
+            let Some(preset) = app_config.get_preset_by_name(&name) else {
+                return Err(not_found("run", &name));
+            };
+
+            let problems = preset.validate();
+            let plan = match problems.is_empty() {
+                true => Some(app_config.create_launch_plan(&name)?),
+                false => None,
+            };
+
+            match format {
+                OutputFormat::Text => {
+                    if let Some(plan) = &plan {
+                        writeln!(out, "{}", plan.program).map_err(|err| err.to_string())?;
+                        for arg in &plan.args {
+                            writeln!(out, "{}", arg).map_err(|err| err.to_string())?;
+                        }
+                    }
+                }
+                OutputFormat::Json => {
+                    let report = RunReport {
+                        preset: &name,
+                        problems: problems.clone(),
+                        plan,
+                    };
+                    write_json(out, &report)?;
+                }
+            }
+
+            if !problems.is_empty() {
+                return Err(format!("Cannot run preset: {}", problems.join(" ")));
+            }
+        }
+        Command::Run { name, dry_run: false } => {
+            if app_config.get_preset_by_name(&name).is_none() {
+                return Err(not_found("run", &name));
+            }
+
+            let plan = app_config.create_launch_plan(&name)?;
+            plan.run()?;
+        }
+        Command::Create { name, tabs, windows, cmds, tags } => {
+            let messages = create_messages(&app_config, name, tabs, windows, cmds)?;
+
+            let mut preset = Preset::from_input(&messages);
+            preset.preset_info = app_config.settings.preset_defaults.clone();
+            preset.tags = tags
+                .iter()
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect();
+            app_config.add_presets(vec![preset]);
+
+            app_config.write_preset_to_file(&[], WriteType::Edit, config_path)?;
+            writeln!(out, "Preset '{}' created.", messages[0]).map_err(|err| err.to_string())?;
+        }
+        Command::Delete { name, permanent: false } => {
+            app_config.trash_preset_by_name(&name, SystemTime::now())?;
+
+            app_config.write_preset_to_file(&[], WriteType::Edit, config_path)?;
+            writeln!(out, "Preset '{}' moved to the trash.", name).map_err(|err| err.to_string())?;
+        }
+        Command::Delete { name, permanent: true } => {
+            app_config.delete_preset_by_name(&name)?;
+
+            app_config.write_preset_to_file(&[], WriteType::Edit, config_path)?;
+            writeln!(out, "Preset '{}' deleted.", name).map_err(|err| err.to_string())?;
+        }
+        Command::Set { name, field, value } => {
+            let Some(preset) = app_config.get_preset_by_name(&name) else {
+                return Err(not_found("edit", &name));
+            };
+            let problems = preset.validate();
+            if !problems.is_empty() {
+                return Err(format!("Cannot edit preset: {}", problems.join(" ")));
+            }
+
+            let target = parse_field(preset, &field)?;
+            let Some(mut form_field) = preset
+                .form_fields()
+                .into_iter()
+                .find(|form_field| form_field.get_target() == target) else {
+                return Err(format!("Cannot edit preset: UNKNOWN FIELD '{}'.", field));
+            };
+            form_field
+                .submit(value.trim())
+                .map_err(|err| format!("Cannot edit preset: {}", err))?;
+
+            let new_name = value.trim();
+            if
+                target == FieldTarget::Name &&
+                new_name != name &&
+                app_config.get_preset_by_name(new_name).is_some()
+            {
+                return Err(format!("Cannot edit preset: PRESET '{}' ALREADY EXISTS.", new_name));
+            }
+
+            let preset = app_config.get_mut_preset_by_name(&name).unwrap();
+            preset.apply_field(&form_field)?;
+
+            app_config.write_preset_to_file(&[], WriteType::Edit, config_path)?;
+            writeln!(out, "Preset '{}' updated.", name).map_err(|err| err.to_string())?;
+        }
+        Command::Completions { .. } | Command::Doctor => unreachable!(),
+    }
+
+    Ok(())
+}
+
+fn run_doctor(
+    config_path: &str,
+    log_path: &str,
+    format: OutputFormat,
+    out: &mut impl Write
+) -> Result<(), String> {
+    let checks = doctor::run_checks(config_path, log_path);
+    let failed = checks
+        .iter()
+        .filter(|check| check.get_status() == CheckStatus::Fail)
+        .count();
+
+    match format {
+        OutputFormat::Text => {
+            for line in checks.iter().flat_map(|check| check.to_lines()) {
+                writeln!(out, "{}", line).map_err(|err| err.to_string())?;
+            }
+        }
+        OutputFormat::Json => write_json(out, &(DoctorReport { ok: failed == 0, checks }))?,
+    }
+
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("{} check(s) failed.", failed)),
+    }
+}
+
+fn write_json(out: &mut impl Write, value: &impl Serialize) -> Result<(), String> {
+    serde_json::to_writer_pretty(&mut *out, value).map_err(|err| err.to_string())?;
+    writeln!(out).map_err(|err| err.to_string())
+}
+
+fn not_found(action: &str, name: &str) -> String {
+    format!("Cannot {} preset '{}': PRESET WITH GIVEN NAME WAS NOT FOUND.", action, name)
+}
+
+/// Builds the same answer list the `CreatePreset` prompts collect, so that
+/// `Preset::from_input` can be reused as is.
+fn create_messages(
+    app_config: &AppConfig,
+    name: String,
+    tabs: u8,
+    windows: Vec<u8>,
+    cmds: Vec<String>
+) -> Result<Vec<String>, String> {
+    let name = name.trim().to_string();
+
+    if name.is_empty() {
+        return Err(String::from("Cannot create preset: NAME CANNOT BE EMPTY."));
+    }
+    if app_config.get_preset_by_name(&name).is_some() {
+        return Err(format!("Cannot create preset: PRESET '{}' ALREADY EXISTS.", name));
+    }
+    if !(1..=10).contains(&tabs) {
+        return Err(String::from("Cannot create preset: TABS MUST BE BETWEEN 1 AND 10."));
+    }
+    if windows.len() != tabs as usize {
+        return Err(
+            format!(
+                "Cannot create preset: EXPECTED {} WINDOWS VALUES, GOT {}.",
+                tabs,
+                windows.len()
+            )
+        );
+    }
+    if windows.iter().any(|w| !(1..=4).contains(w)) {
+        return Err(String::from("Cannot create preset: WINDOWS MUST BE BETWEEN 1 AND 4."));
+    }
+
+    let total_windows = windows
+        .iter()
+        .map(|w| *w as usize)
+        .sum::<usize>();
+    if cmds.len() != total_windows {
+        return Err(
+            format!(
+                "Cannot create preset: EXPECTED {} --cmd VALUES (ONE PER WINDOW), GOT {}.",
+                total_windows,
+                cmds.len()
+            )
+        );
+    }
+
+    let mut messages = vec![name, tabs.to_string()];
+    messages.extend(windows.iter().map(|w| w.to_string()));
+    messages.extend(cmds.iter().map(|cmd| cmd.trim().trim_end_matches(',').to_string()));
+
+    Ok(messages)
+}
+
+fn parse_field(preset: &Preset, field: &str) -> Result<FieldTarget, String> {
+    let invalid = || format!("Cannot edit preset: UNKNOWN FIELD '{}'.", field);
+    let parse_index = |part: &str| -> Result<usize, String> {
+        match part.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n - 1),
+            _ => Err(invalid()),
+        }
+    };
+
+    let parts = field.split('.').collect::<Vec<&str>>();
+
+    let target = match parts.as_slice() {
+        ["name"] => FieldTarget::Name,
+        ["tabs"] => FieldTarget::Tabs,
+        ["tags"] => FieldTarget::Tags,
+        ["wt_profile"] => FieldTarget::WtProfile,
+        ["init_shell"] => FieldTarget::InitShell,
+        ["target_shell"] => FieldTarget::TargetShell,
+        ["tab", tab, "windows"] => {
+            let tab = parse_index(tab)?;
+            if tab >= preset.windows.len() {
+                return Err(String::from("Cannot edit preset: TAB INDEX OUT OF BOUNDS."));
+            }
+            FieldTarget::Windows(tab)
+        }
+        ["tab", tab, "window", window] => {
+            let (tab, window) = (parse_index(tab)?, parse_index(window)?);
+            let Some(arg_index) = preset.get_arg_index(tab, window) else {
+                return Err(String::from("Cannot edit preset: WINDOW INDEX OUT OF BOUNDS."));
+            };
+            FieldTarget::Args(arg_index)
+        }
+        _ => {
+            return Err(invalid());
+        }
+    };
+
+    Ok(target)
+}
@@ -0,0 +1,27 @@
+use clap::ValueEnum;
+
+const BASH: &str = include_str!("completions/workspace_opener.bash");
+const ZSH: &str = include_str!("completions/_workspace_opener");
+const FISH: &str = include_str!("completions/workspace_opener.fish");
+const POWERSHELL: &str = include_str!("completions/workspace_opener.ps1");
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl CompletionShell {
+    /// Completion script for the shell. Preset names are completed by the
+    /// script running `workspace_opener list` against the current config.
+    pub fn script(&self) -> &'static str {
+        match self {
+            CompletionShell::Bash => BASH,
+            CompletionShell::Zsh => ZSH,
+            CompletionShell::Fish => FISH,
+            CompletionShell::Powershell => POWERSHELL,
+        }
+    }
+}
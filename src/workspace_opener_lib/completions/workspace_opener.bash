@@ -0,0 +1,98 @@
+# bash completion for workspace_opener
+# Preset names are read from the config by running `workspace_opener list`.
+
+_workspace_opener_presets() {
+    local IFS=$'\n' name
+    for name in $("${COMP_WORDS[0]}" "${config_args[@]}" list 2>/dev/null); do
+        if [[ "$name" == "$cur"* ]]; then
+            COMPREPLY+=("$(printf '%q' "$name")")
+        fi
+    done
+}
+
+_workspace_opener() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local commands="list show run create delete set doctor completions help"
+    local fields="name tabs wt_profile init_shell target_shell tags tab."
+    local shells="powershell pwsh cmd bash zsh fish"
+    local config_args=() positionals=() subcommand="" i
+    COMPREPLY=()
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            --config)
+                config_args=(--config "${COMP_WORDS[i+1]}")
+                ((i++))
+                ;;
+            --format|--name|--tabs|--windows|--cmd|--tag)
+                ((i++))
+                ;;
+            -*)
+                ;;
+            *)
+                if [[ -z "$subcommand" ]]; then
+                    subcommand="${COMP_WORDS[i]}"
+                else
+                    positionals+=("${COMP_WORDS[i]}")
+                fi
+                ;;
+        esac
+    done
+
+    case "$prev" in
+        --config)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            return
+            ;;
+        --format)
+            COMPREPLY=($(compgen -W "text json" -- "$cur"))
+            return
+            ;;
+        --name|--tabs|--windows|--cmd|--tag)
+            return
+            ;;
+    esac
+
+    if [[ "$cur" == -* ]]; then
+        local flags="--config --format --help"
+        case "$subcommand" in
+            "") flags+=" --version" ;;
+            run) flags+=" --dry-run" ;;
+            delete) flags+=" --permanent" ;;
+            create) flags+=" --name --tabs --windows --cmd --tag" ;;
+        esac
+        COMPREPLY=($(compgen -W "$flags" -- "$cur"))
+        return
+    fi
+
+    if [[ -z "$subcommand" ]]; then
+        COMPREPLY=($(compgen -W "$commands" -- "$cur"))
+        return
+    fi
+
+    case "$subcommand:${#positionals[@]}" in
+        show:0|run:0|delete:0|set:0)
+            _workspace_opener_presets
+            ;;
+        set:1)
+            compopt -o nospace 2>/dev/null
+            COMPREPLY=($(compgen -W "$fields" -- "$cur"))
+            [[ ${#COMPREPLY[@]} -eq 1 && "${COMPREPLY[0]}" != "tab." ]] && COMPREPLY=("${COMPREPLY[0]} ")
+            ;;
+        set:2)
+            case "${positionals[1]}" in
+                init_shell) COMPREPLY=($(compgen -W "powershell pwsh cmd" -- "$cur")) ;;
+                target_shell) COMPREPLY=($(compgen -W "$shells" -- "$cur")) ;;
+            esac
+            ;;
+        completions:0)
+            COMPREPLY=($(compgen -W "bash zsh fish powershell" -- "$cur"))
+            ;;
+        help:0)
+            COMPREPLY=($(compgen -W "$commands" -- "$cur"))
+            ;;
+    esac
+}
+
+complete -F _workspace_opener workspace_opener workspace_opener.exe
@@ -0,0 +1,79 @@
+# fish completion for workspace_opener
+# Preset names are read from the config by running `workspace_opener list`.
+
+set -l commands list show run create delete set doctor completions help
+
+# Prints the subcommand followed by its positional arguments.
+function __workspace_opener_positionals
+    set -l tokens (commandline -opc)
+    set -e tokens[1]
+    set -l skip 0
+    for token in $tokens
+        if test $skip -eq 1
+            set skip 0
+            continue
+        end
+        switch $token
+            case --config --format --name --tabs --windows --cmd --tag
+                set skip 1
+            case '-*'
+            case '*'
+                echo $token
+        end
+    end
+end
+
+function __workspace_opener_at
+    set -l positionals (__workspace_opener_positionals)
+    test (count $positionals) -eq (math $argv[1] + 1)
+    and contains -- $positionals[1] $argv[2..-1]
+end
+
+function __workspace_opener_presets
+    set -l tokens (commandline -opc)
+    set -l config_args
+    if set -l index (contains -i -- --config $tokens)
+        set config_args --config $tokens[(math $index + 1)]
+    end
+    $tokens[1] $config_args list 2>/dev/null
+end
+
+function __workspace_opener_field_is
+    set -l positionals (__workspace_opener_positionals)
+    test (count $positionals) -eq 3
+    and test "$positionals[1]" = set
+    and test "$positionals[3]" = $argv[1]
+end
+
+for program in workspace_opener workspace_opener.exe
+    complete -c $program -f
+    complete -c $program -l config -r -F -d 'Path to the config file'
+    complete -c $program -l format -x -a 'text json' -d 'Output format'
+    complete -c $program -s h -l help -d 'Print help'
+    complete -c $program -s V -l version -n "not __fish_seen_subcommand_from $commands" -d 'Print version'
+
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a list -d 'List the names of all presets'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a show -d 'Show every field of a preset'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a run -d 'Open a preset in Windows Terminal'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a create -d 'Create a new preset'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a delete -d 'Move a preset to the trash'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a set -d 'Change a single field of a preset'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a doctor -d 'Check that presets can be opened on this machine'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a completions -d 'Print a shell completion script'
+    complete -c $program -n "not __fish_seen_subcommand_from $commands" -a help -d 'Print help of the given subcommand'
+
+    complete -c $program -n '__workspace_opener_at 0 show run delete set' -a '(__workspace_opener_presets)'
+    complete -c $program -n '__workspace_opener_at 1 set' -a 'name tabs wt_profile init_shell target_shell tags tab.'
+    complete -c $program -n '__workspace_opener_field_is init_shell' -a 'powershell pwsh cmd'
+    complete -c $program -n '__workspace_opener_field_is target_shell' -a 'powershell pwsh cmd bash zsh fish'
+    complete -c $program -n '__workspace_opener_at 0 completions' -a 'bash zsh fish powershell'
+    complete -c $program -n '__workspace_opener_at 0 help' -a "$commands"
+
+    complete -c $program -n '__fish_seen_subcommand_from run' -l dry-run -d 'Print the launch plan instead of opening it'
+    complete -c $program -n '__fish_seen_subcommand_from delete' -l permanent -d 'Delete for good instead of moving to the trash'
+    complete -c $program -n '__fish_seen_subcommand_from create' -l name -x -d 'Preset name'
+    complete -c $program -n '__fish_seen_subcommand_from create' -l tabs -x -a '1 2 3 4 5 6 7 8 9 10' -d 'Amount of tabs'
+    complete -c $program -n '__fish_seen_subcommand_from create' -l windows -x -d 'Amount of windows for every tab'
+    complete -c $program -n '__fish_seen_subcommand_from create' -l cmd -x -d 'Commands for one window'
+    complete -c $program -n '__fish_seen_subcommand_from create' -l tag -x -d 'Tag used by the TUI search'
+end
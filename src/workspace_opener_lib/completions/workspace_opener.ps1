@@ -0,0 +1,104 @@
+# PowerShell completion for workspace_opener
+# Preset names are read from the config by running `workspace_opener list`.
+
+Register-ArgumentCompleter -Native -CommandName 'workspace_opener', 'workspace_opener.exe' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $commands = [ordered]@{
+        'list'        = 'List the names of all presets'
+        'show'        = 'Show every field of a preset'
+        'run'         = 'Open a preset in Windows Terminal'
+        'create'      = 'Create a new preset'
+        'delete'      = 'Move a preset to the trash'
+        'set'         = 'Change a single field of a preset'
+        'doctor'      = 'Check that presets can be opened on this machine'
+        'completions' = 'Print a shell completion script'
+        'help'        = 'Print help of the given subcommand'
+    }
+    $valueFlags = '--config', '--format', '--name', '--tabs', '--windows', '--cmd', '--tag'
+
+    $elements = @($commandAst.CommandElements | Where-Object { $_.Extent.StartOffset -lt $cursorPosition })
+    $program = $elements[0].ToString()
+    $words = @($elements | Select-Object -Skip 1 | ForEach-Object { $_.ToString() })
+    if ($wordToComplete -ne '' -and $words.Count -gt 0) {
+        $words = @($words | Select-Object -SkipLast 1)
+    }
+
+    $configArgs = @()
+    $positionals = @()
+    for ($i = 0; $i -lt $words.Count; $i++) {
+        $word = $words[$i]
+        if ($word -eq '--config' -and $i + 1 -lt $words.Count) {
+            $configArgs = @('--config', $words[$i + 1])
+        }
+        if ($valueFlags -contains $word) {
+            $i++
+        } elseif (-not $word.StartsWith('-')) {
+            $positionals += $word.Trim("'", '"')
+        }
+    }
+
+    $previous = if ($words.Count -gt 0) { $words[-1] } else { '' }
+    $results = @()
+
+    function Complete-Values($values, $type = 'ParameterValue') {
+        foreach ($value in $values) {
+            if ($value -like "$wordToComplete*") {
+                $text = if ($value -match '\s') { "'$value'" } else { $value }
+                [System.Management.Automation.CompletionResult]::new($text, $value, $type, $value)
+            }
+        }
+    }
+
+    if ($previous -eq '--format') {
+        return Complete-Values @('text', 'json')
+    }
+    if ($valueFlags -contains $previous) {
+        return
+    }
+
+    $subcommand = if ($positionals.Count -gt 0) { $positionals[0] } else { $null }
+
+    if ($wordToComplete.StartsWith('-')) {
+        $flags = @('--config', '--format', '--help')
+        switch ($subcommand) {
+            $null { $flags += '--version' }
+            'run' { $flags += '--dry-run' }
+            'delete' { $flags += '--permanent' }
+            'create' { $flags += '--name', '--tabs', '--windows', '--cmd', '--tag' }
+        }
+        return Complete-Values $flags 'ParameterName'
+    }
+
+    if ($null -eq $subcommand) {
+        foreach ($command in $commands.Keys) {
+            if ($command -like "$wordToComplete*") {
+                $results += [System.Management.Automation.CompletionResult]::new($command, $command, 'Command', $commands[$command])
+            }
+        }
+        return $results
+    }
+
+    $index = $positionals.Count - 1
+    switch ("${subcommand}:$index") {
+        { $_ -in 'show:0', 'run:0', 'delete:0', 'set:0' } {
+            $presets = @(& $program @configArgs list 2>$null)
+            return Complete-Values $presets
+        }
+        'set:1' {
+            return Complete-Values @('name', 'tabs', 'wt_profile', 'init_shell', 'target_shell', 'tags', 'tab.')
+        }
+        'set:2' {
+            switch ($positionals[2]) {
+                'init_shell' { return Complete-Values @('powershell', 'pwsh', 'cmd') }
+                'target_shell' { return Complete-Values @('powershell', 'pwsh', 'cmd', 'bash', 'zsh', 'fish') }
+            }
+        }
+        'completions:0' {
+            return Complete-Values @('bash', 'zsh', 'fish', 'powershell')
+        }
+        'help:0' {
+            return Complete-Values $commands.Keys
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use super::app::{ InputMode, State };
+use crossterm::{
+    cursor,
+    event::{ DisableMouseCapture, EnableMouseCapture },
+    execute,
+    terminal::{ disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen },
+};
+use std::{
+    backtrace::Backtrace,
+    fs,
+    io,
+    panic,
+    path::{ Path, PathBuf },
+    sync::Mutex,
+    time::{ SystemTime, UNIX_EPOCH },
+};
+
+/// What the TUI was doing, written into the crash report when it panics.
+static CONTEXT: Mutex<Option<String>> = Mutex::new(None);
+
+/// Raw mode, the alternate screen and mouse capture, turned off again when the
+/// guard is dropped. Errors returned from `main` and panics unwind through the
+/// guard, so the terminal is usable afterwards either way.
+pub struct TerminalGuard {
+    _private: (),
+}
+
+impl TerminalGuard {
+    pub fn enter() -> io::Result<TerminalGuard> {
+        enable_raw_mode()?;
+        // Built before the screen is switched, so a failure below still restores raw mode.
+        let guard = TerminalGuard { _private: () };
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leaves raw mode and the alternate screen and shows the cursor. Safe to call
+/// more than once, errors are ignored as there is nothing left to report them to.
+pub fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, cursor::Show);
+}
+
+/// Records the screen shown, for the crash report of a later panic.
+pub fn set_context(state: State, input_mode: &InputMode) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        *context = Some(format!("State: {:?}\nInputMode: {:?}", state, input_mode));
+    }
+}
+
+/// Restores the terminal before the panic message is printed and writes a crash
+/// report into `state_dir`. The previous hook still prints the message.
+pub fn install_panic_hook(state_dir: PathBuf) {
+    let previous = panic::take_hook();
+
+    panic::set_hook(
+        Box::new(move |info| {
+            restore_terminal();
+
+            let context = CONTEXT.lock()
+                .ok()
+                .and_then(|context| context.clone());
+            let backtrace = Backtrace::force_capture().to_string();
+            let report = crash_report(&info.to_string(), context.as_deref(), &backtrace);
+
+            previous(info);
+            match write_crash_report(&state_dir, &report) {
+                Ok(path) => eprintln!("A crash report was written to {}.", path.display()),
+                Err(err) => eprintln!("Cannot write the crash report: {}", err),
+            }
+        })
+    );
+}
+
+/// The text of a crash report. `context` is left out when the TUI never drew a screen.
+pub fn crash_report(message: &str, context: Option<&str>, backtrace: &str) -> String {
+    let mut report = format!("workspace_opener {} crashed.\n\n", env!("CARGO_PKG_VERSION"));
+    if let Some(context) = context {
+        report.push_str(context);
+        report.push_str("\n\n");
+    }
+    report.push_str(message);
+    report.push_str("\n\nBacktrace:\n");
+    report.push_str(backtrace);
+    report
+}
+
+/// Writes `report` to `crash-<unix seconds>.txt` in `state_dir` and returns its path.
+pub fn write_crash_report(state_dir: &Path, report: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(state_dir)?;
+
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let path = state_dir.join(format!("crash-{}.txt", seconds));
+    fs::write(&path, report)?;
+
+    Ok(path)
+}
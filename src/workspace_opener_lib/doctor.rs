@@ -0,0 +1,474 @@
+use super::model::{ AppConfig, Preset, ShellType };
+#[cfg(feature = "tui")]
+use super::{ keymap::Keymap, model::{ KeymapSettings, ThemeSettings }, theme::Theme };
+use serde::Serialize;
+use std::{ env, fs::{ self, OpenOptions }, path::{ Path, PathBuf } };
+
+/// Windows Terminal keeps its settings in one of these locations under `%LOCALAPPDATA%`,
+/// depending on whether it was installed from the Store, as Preview, or unpackaged.
+const WT_SETTINGS_PATHS: [&str; 3] = [
+    "Packages/Microsoft.WindowsTerminal_8wekyb3d8bbwe/LocalState/settings.json",
+    "Packages/Microsoft.WindowsTerminalPreview_8wekyb3d8bbwe/LocalState/settings.json",
+    "Microsoft/Windows Terminal/settings.json",
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Check {
+    pub(super) name: String,
+    pub(super) status: CheckStatus,
+    pub(super) message: String,
+    pub(super) hint: Option<String>,
+}
+
+impl Check {
+    pub fn get_status(&self) -> CheckStatus {
+        self.status
+    }
+
+    fn pass(name: &str, message: String) -> Check {
+        Check { name: name.to_string(), status: CheckStatus::Pass, message, hint: None }
+    }
+
+    fn warn(name: &str, message: String, hint: &str) -> Check {
+        Check {
+            name: name.to_string(),
+            status: CheckStatus::Warn,
+            message,
+            hint: Some(hint.to_string()),
+        }
+    }
+
+    fn fail(name: &str, message: String, hint: &str) -> Check {
+        Check {
+            name: name.to_string(),
+            status: CheckStatus::Fail,
+            message,
+            hint: Some(hint.to_string()),
+        }
+    }
+
+    /// One line per check plus an indented hint line, as printed by `workspace_opener doctor`.
+    pub fn to_lines(&self) -> Vec<String> {
+        let status = match self.status {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        };
+
+        let mut lines = vec![format!("[{}] {}: {}", status, self.name, self.message)];
+        if let Some(hint) = &self.hint {
+            lines.push(format!("       hint: {}", hint));
+        }
+        lines
+    }
+}
+
+/// Runs every check against the config at `config_path`. Preset checks are
+/// skipped when the config cannot be loaded.
+pub fn run_checks(config_path: &str, log_path: &str) -> Vec<Check> {
+    let mut checks = vec![];
+
+    let app_config = match AppConfig::load(config_path) {
+        Ok(app_config) => {
+            checks.push(
+                Check::pass(
+                    "Config",
+                    format!("'{}' parsed, {} preset(s).", config_path, app_config.presets.len())
+                )
+            );
+            Some(app_config)
+        }
+        Err(err) => {
+            checks.push(
+                Check::fail(
+                    "Config",
+                    err,
+                    "Restore the config from a backup or replace it with {\"presets\":[],\"settings\":{\"debug_mode\":false}}."
+                )
+            );
+            None
+        }
+    };
+
+    checks.push(check_executable("Windows Terminal", "wt.exe", WT_HINT));
+
+    if let Some(app_config) = &app_config {
+        checks.extend(check_shells(&app_config.presets));
+        checks.extend(check_profiles(&app_config.presets));
+        checks.extend(check_directories(&app_config.presets));
+        checks.extend(check_presets(&app_config.presets));
+        // Keys and colors only matter to the TUI.
+        #[cfg(feature = "tui")]
+        {
+            checks.push(check_keymap(&app_config.settings.keymap));
+            checks.push(check_theme(&app_config.settings.theme));
+        }
+    }
+
+    checks.push(check_log_file(log_path));
+
+    checks
+}
+
+const WT_HINT: &str =
+    "Install Windows Terminal from the Microsoft Store and make sure its app execution alias is enabled.";
+
+fn check_executable(name: &str, program: &str, hint: &str) -> Check {
+    match find_executable(program) {
+        Some(path) => Check::pass(name, format!("'{}' found at {}.", program, path.display())),
+        None => Check::fail(name, format!("'{}' was not found on PATH.", program), hint),
+    }
+}
+
+fn check_shells(presets: &[Preset]) -> Vec<Check> {
+    let mut shells: Vec<ShellType> = vec![];
+    for preset in presets {
+        for shell in [&preset.preset_info.init_shell, &preset.preset_info.target_shell] {
+            if !shells.contains(shell) {
+                shells.push(shell.clone());
+            }
+        }
+    }
+
+    let mut checks = vec![];
+    let mut wsl_checked = false;
+
+    for shell in shells {
+        let (program, hint) = match shell {
+            ShellType::WindowsPowershell =>
+                ("powershell.exe", "Windows PowerShell ships with Windows; check that System32\\WindowsPowerShell\\v1.0 is on PATH."),
+            ShellType::Powershell =>
+                ("pwsh.exe", "Install PowerShell 7 (winget install Microsoft.PowerShell) or pick powershell instead."),
+            ShellType::Cmd => ("cmd.exe", "cmd ships with Windows; check that System32 is on PATH."),
+            ShellType::Bash | ShellType::Zsh | ShellType::Fish => {
+                if wsl_checked {
+                    continue;
+                }
+                wsl_checked = true;
+                ("wsl.exe", "Install WSL with `wsl --install` and make sure bash, zsh or fish is installed inside the distribution.")
+            }
+        };
+
+        checks.push(check_executable(&format!("Shell {}", shell), program, hint));
+    }
+
+    checks
+}
+
+fn check_profiles(presets: &[Preset]) -> Vec<Check> {
+    let referenced = presets
+        .iter()
+        .filter(|preset| !preset.preset_info.wt_profile.is_empty())
+        .collect::<Vec<&Preset>>();
+
+    if referenced.is_empty() {
+        return vec![];
+    }
+
+    let Some(profiles) = read_wt_profiles() else {
+        return vec![
+            Check::warn(
+                "Profiles",
+                String::from("Windows Terminal settings.json was not found, profiles cannot be checked."),
+                "Open Windows Terminal once so it writes its settings, then run doctor again."
+            )
+        ];
+    };
+
+    referenced
+        .iter()
+        .map(|preset| {
+            let profile = &preset.preset_info.wt_profile;
+            let name = format!("Profile of '{}'", preset.name);
+            if profiles.contains(profile) {
+                Check::pass(&name, format!("'{}' exists.", profile))
+            } else {
+                Check::fail(
+                    &name,
+                    format!("'{}' is not a Windows Terminal profile.", profile),
+                    &format!(
+                        "Use one of: {}. Leave it empty to use the default profile.",
+                        profiles.join(", ")
+                    )
+                )
+            }
+        })
+        .collect()
+}
+
+/// Checks the targets of `cd` commands that run in a Windows shell. Paths in
+/// WSL shells live inside the distribution and are not visible from here.
+fn check_directories(presets: &[Preset]) -> Vec<Check> {
+    let mut checks = vec![];
+
+    for preset in presets {
+        match preset.preset_info.target_shell {
+            ShellType::Bash | ShellType::Zsh | ShellType::Fish => {
+                continue;
+            }
+            _ => {}
+        }
+
+        for dir in preset.args.iter().flat_map(|arg| cd_targets(arg)) {
+            let name = format!("Directory in '{}'", preset.name);
+            let Some(path) = resolve_windows_dir(&dir) else {
+                continue;
+            };
+
+            if path.is_dir() {
+                checks.push(Check::pass(&name, format!("'{}' exists.", dir)));
+            } else {
+                checks.push(
+                    Check::fail(
+                        &name,
+                        format!("'{}' does not exist.", dir),
+                        "Fix the cd command of the window or create the directory."
+                    )
+                );
+            }
+        }
+    }
+
+    checks
+}
+
+fn check_presets(presets: &[Preset]) -> Vec<Check> {
+    presets
+        .iter()
+        .filter_map(|preset| {
+            let problems = preset.validate();
+            if problems.is_empty() {
+                return None;
+            }
+            Some(
+                Check::fail(
+                    &format!("Preset '{}'", preset.name),
+                    problems.join(" "),
+                    "Edit the preset in the TUI or with `workspace_opener set`."
+                )
+            )
+        })
+        .collect()
+}
+
+#[cfg(feature = "tui")]
+fn check_keymap(settings: &KeymapSettings) -> Check {
+    match Keymap::new(settings) {
+        Ok(_) => Check::pass("Keybindings", format!("{:?} keymap, no conflicts.", settings.preset)),
+        Err(err) =>
+            Check::fail(
+                "Keybindings",
+                err,
+                "Fix `settings.keymap.bindings` in the config, the TUI uses the default keys until then."
+            ),
+    }
+}
+
+#[cfg(feature = "tui")]
+fn check_theme(settings: &ThemeSettings) -> Check {
+    match Theme::new(settings, false) {
+        Ok(_) => Check::pass("Theme", format!("'{}' theme.", settings.name)),
+        Err(err) =>
+            Check::fail(
+                "Theme",
+                err,
+                "Fix `settings.theme` in the config, the TUI uses the dark theme until then."
+            ),
+    }
+}
+
+fn check_log_file(log_path: &str) -> Check {
+    if let Some(parent) = Path::new(log_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            let _ = fs::create_dir_all(parent);
+        }
+    }
+
+    match OpenOptions::new().create(true).append(true).open(log_path) {
+        Ok(_) => Check::pass("Log file", format!("'{}' is writable.", log_path)),
+        Err(err) =>
+            Check::fail(
+                "Log file",
+                format!("'{}' cannot be written: {}", log_path, err),
+                "Point `settings.log.path` in the config or `--log-file` to a writable location, or run the app as an administrator."
+            ),
+    }
+}
+
+/// Looks `program` up in every `PATH` directory, trying `PATHEXT` extensions
+/// when the name has none.
+pub fn find_executable(program: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    let extensions = env
+        ::var("PATHEXT")
+        .map(|exts| {
+            exts.split(';')
+                .map(|ext| ext.to_string())
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+
+    for dir in env::split_paths(&path) {
+        let candidate = dir.join(program);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if Path::new(program).extension().is_none() {
+            for ext in &extensions {
+                let candidate = dir.join(format!("{}{}", program, ext.to_lowercase()));
+                if candidate.is_file() {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Profile names from the Windows Terminal settings, `None` when they are not found.
+pub fn read_wt_profiles() -> Option<Vec<String>> {
+    let local_app_data = PathBuf::from(env::var_os("LOCALAPPDATA")?);
+
+    let settings = WT_SETTINGS_PATHS.iter()
+        .map(|path| local_app_data.join(path))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect::<Vec<String>>();
+
+    if settings.is_empty() {
+        return None;
+    }
+
+    let mut profiles = vec![];
+    for content in settings {
+        profiles.extend(parse_wt_profiles(&content));
+    }
+    Some(profiles)
+}
+
+/// Profile names from a Windows Terminal `settings.json`. The file allows
+/// comments, so they are stripped before parsing.
+pub fn parse_wt_profiles(content: &str) -> Vec<String> {
+    let Ok(settings) = serde_json::from_str::<serde_json::Value>(&strip_json_comments(content)) else {
+        return vec![];
+    };
+
+    let profiles = match &settings["profiles"] {
+        serde_json::Value::Array(list) => list,
+        serde_json::Value::Object(obj) =>
+            match obj.get("list") {
+                Some(serde_json::Value::Array(list)) => list,
+                _ => {
+                    return vec![];
+                }
+            }
+        _ => {
+            return vec![];
+        }
+    };
+
+    profiles
+        .iter()
+        .filter_map(|profile| profile["name"].as_str().map(|name| name.to_string()))
+        .collect()
+}
+
+fn strip_json_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(ch) = chars.next() {
+        if in_string {
+            result.push(ch);
+            match ch {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        result.push(escaped);
+                    }
+                }
+                '"' => {
+                    in_string = false;
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        match (ch, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                result.push(ch);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        result.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => result.push(ch),
+        }
+    }
+
+    result
+}
+
+/// Directories passed to `cd` in a window arg, e.g. `ls, cd Rust\` Projects, pwd`
+/// gives `Rust Projects`.
+pub fn cd_targets(arg: &str) -> Vec<String> {
+    arg.split(',')
+        .filter_map(|command| {
+            let command = command.trim();
+            let dir = command
+                .strip_prefix("cd ")
+                .or_else(|| command.strip_prefix("Set-Location "))?;
+            let dir = dir.trim().trim_matches('"').trim_matches('\'').replace("` ", " ");
+            match dir.is_empty() {
+                true => None,
+                false => Some(dir),
+            }
+        })
+        .collect()
+}
+
+/// Absolute Windows paths and `~` paths resolve against the filesystem,
+/// relative ones depend on the profile's starting directory and are skipped.
+fn resolve_windows_dir(dir: &str) -> Option<PathBuf> {
+    if let Some(rest) = dir.strip_prefix('~') {
+        let home = env::var_os("USERPROFILE").or_else(|| env::var_os("HOME"))?;
+        return Some(PathBuf::from(home).join(rest.trim_start_matches(['/', '\\'])));
+    }
+
+    let bytes = dir.as_bytes();
+    let is_drive_path =
+        bytes.len() >= 3 &&
+        bytes[0].is_ascii_alphabetic() &&
+        bytes[1] == b':' &&
+        (bytes[2] == b'\\' || bytes[2] == b'/');
+
+    if is_drive_path || dir.starts_with("\\\\") {
+        Some(PathBuf::from(dir))
+    } else {
+        None
+    }
+}
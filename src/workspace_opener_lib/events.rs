@@ -0,0 +1,185 @@
+use crossterm::event::{ self, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent };
+use std::{
+    collections::VecDeque,
+    fs,
+    path::PathBuf,
+    sync::mpsc::{ self, Receiver, Sender },
+    thread,
+    time::{ Duration, Instant, SystemTime },
+};
+
+/// How often `AppEvent::Tick` is sent when no input arrives.
+pub const TICK_RATE: Duration = Duration::from_millis(250);
+
+/// How often the config file is checked for changes made outside the app.
+pub const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Everything the main loop reacts to, from the terminal or from the app itself.
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Resize(u16, u16),
+    /// Sent every tick rate, so timers run without input.
+    Tick,
+    Message(AppMessage),
+}
+
+/// Messages from background tasks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppMessage {
+    /// The config file was written, by the app or by another program.
+    ConfigChanged,
+    /// A preset launched in the background has started, or failed to.
+    Launched {
+        name: String,
+        result: Result<(), String>,
+    },
+}
+
+/// Where `run_app` takes its events from: the terminal, or a script in tests.
+pub trait EventSource {
+    /// Waits for the next event. `None` ends the app.
+    fn next(&mut self) -> Option<AppEvent>;
+
+    /// Lets a background task post into the queue.
+    fn sender(&self) -> Sender<AppEvent>;
+}
+
+/// One queue of `AppEvent`s fed by the terminal reader thread and by every
+/// `Sender` handed out with `sender`.
+pub struct Events {
+    sender: Sender<AppEvent>,
+    receiver: Receiver<AppEvent>,
+}
+
+impl Events {
+    /// Starts reading the terminal. The reader stops once `Events` is dropped.
+    pub fn new(tick_rate: Duration) -> Events {
+        let (sender, receiver) = mpsc::channel();
+
+        let terminal_sender = sender.clone();
+        thread::spawn(move || read_terminal(terminal_sender, tick_rate));
+
+        Events { sender, receiver }
+    }
+}
+
+impl EventSource for Events {
+    fn next(&mut self) -> Option<AppEvent> {
+        self.receiver.recv().ok()
+    }
+
+    fn sender(&self) -> Sender<AppEvent> {
+        self.sender.clone()
+    }
+}
+
+/// Plays a fixed list of events, then ends the app. Messages posted by
+/// background tasks are handled before the next scripted event.
+pub struct ScriptedEvents {
+    script: VecDeque<AppEvent>,
+    sender: Sender<AppEvent>,
+    receiver: Receiver<AppEvent>,
+}
+
+impl ScriptedEvents {
+    pub fn new(script: Vec<AppEvent>) -> ScriptedEvents {
+        let (sender, receiver) = mpsc::channel();
+        ScriptedEvents { script: script.into(), sender, receiver }
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn next(&mut self) -> Option<AppEvent> {
+        match self.receiver.try_recv() {
+            Ok(event) => Some(event),
+            Err(_) => self.script.pop_front(),
+        }
+    }
+
+    fn sender(&self) -> Sender<AppEvent> {
+        self.sender.clone()
+    }
+}
+
+/// A press of `code` without modifiers.
+pub fn key(code: KeyCode) -> AppEvent {
+    AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+/// A press of `code` with `modifiers`, e.g. `ctrl+a`.
+pub fn key_with(code: KeyCode, modifiers: KeyModifiers) -> AppEvent {
+    AppEvent::Key(KeyEvent::new(code, modifiers))
+}
+
+/// The key presses that type `text`.
+pub fn typed(text: &str) -> Vec<AppEvent> {
+    text.chars()
+        .map(|ch| key(KeyCode::Char(ch)))
+        .collect()
+}
+
+fn read_terminal(sender: Sender<AppEvent>, tick_rate: Duration) {
+    let mut last_tick = Instant::now();
+
+    loop {
+        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        let event = match event::poll(timeout) {
+            Ok(true) =>
+                match event::read() {
+                    Ok(Event::Key(key)) => Some(AppEvent::Key(key)),
+                    Ok(Event::Mouse(mouse)) => Some(AppEvent::Mouse(mouse)),
+                    Ok(Event::Resize(width, height)) => Some(AppEvent::Resize(width, height)),
+                    Ok(_) => None,
+                    Err(err) => {
+                        log::error!("Cannot read a terminal event: {}", err);
+                        return;
+                    }
+                }
+            Ok(false) => None,
+            Err(err) => {
+                log::error!("Cannot poll for terminal events: {}", err);
+                return;
+            }
+        };
+
+        if let Some(event) = event {
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+        if last_tick.elapsed() >= tick_rate {
+            if sender.send(AppEvent::Tick).is_err() {
+                return;
+            }
+            last_tick = Instant::now();
+        }
+    }
+}
+
+/// Checks the modification time of `path` every `interval` and posts
+/// `AppMessage::ConfigChanged` when it moves. Stops once the queue is gone.
+pub fn watch_config(path: PathBuf, sender: Sender<AppEvent>, interval: Duration) {
+    let modified = |path: &PathBuf| -> Option<SystemTime> {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    };
+
+    thread::spawn(move || {
+        let mut last = modified(&path);
+        loop {
+            thread::sleep(interval);
+
+            let current = modified(&path);
+            if current == last {
+                continue;
+            }
+            last = current;
+            if sender.send(AppEvent::Message(AppMessage::ConfigChanged)).is_err() {
+                return;
+            }
+        }
+    });
+}
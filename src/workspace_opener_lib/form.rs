@@ -0,0 +1,258 @@
+use std::{ fmt::Display, path::Path };
+
+/// What a form field edits. Preset fields point at their tab or window, the
+/// others are `Settings` fields.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldTarget {
+    Name,
+    Tabs,
+    /// Windows of the tab with this index.
+    Windows(usize),
+    /// Commands of the window whose index in `Preset::args` this is.
+    Args(usize),
+    WtProfile,
+    InitShell,
+    TargetShell,
+    Tags,
+    DebugMode,
+    TrashDays,
+    DefaultWtProfile,
+    DefaultInitShell,
+    DefaultTargetShell,
+    CloseAfterLaunch,
+    ConfirmBeforeLaunch,
+    Launcher,
+    Theme,
+    LogLevel,
+    LogFile,
+    BackupDir,
+}
+
+/// A typed field value together with the bounds its validator checks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    /// Free text, `required` rejects an empty one.
+    Text {
+        text: String,
+        required: bool,
+    },
+    Integer {
+        value: i64,
+        min: i64,
+        max: i64,
+    },
+    /// One of `options`, `selected` is its index.
+    Choice {
+        options: Vec<String>,
+        selected: usize,
+    },
+    Toggle(bool),
+    /// A file or directory, `must_exist` rejects one that is not there. Empty
+    /// means no path.
+    Path {
+        path: String,
+        must_exist: bool,
+    },
+    /// Commands run one after another in a window, written separated by commas.
+    Commands(Vec<String>),
+}
+
+/// One editable line of the Settings or Edit Preset screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormField {
+    pub(super) target: FieldTarget,
+    pub(super) label: String,
+    pub(super) value: FieldValue,
+    /// Why the last submitted input was rejected, shown next to the input box.
+    pub(super) error: Option<String>,
+}
+
+impl FieldValue {
+    pub fn text(text: &str, required: bool) -> FieldValue {
+        FieldValue::Text { text: text.to_string(), required }
+    }
+
+    pub fn integer(value: i64, min: i64, max: i64) -> FieldValue {
+        FieldValue::Integer { value, min, max }
+    }
+
+    /// A choice of `options` with `current` selected, or the first option when
+    /// `current` is not one of them.
+    pub fn choice(options: Vec<String>, current: &str) -> FieldValue {
+        let selected = options
+            .iter()
+            .position(|option| option == current)
+            .unwrap_or(0);
+        FieldValue::Choice { options, selected }
+    }
+
+    pub fn commands(commands: &str) -> FieldValue {
+        match commands.is_empty() {
+            true => FieldValue::Commands(Vec::new()),
+            false => FieldValue::Commands(commands.split(',').map(String::from).collect()),
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            FieldValue::Integer { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_toggle(&self) -> Option<bool> {
+        match self {
+            FieldValue::Toggle(on) => Some(*on),
+            _ => None,
+        }
+    }
+
+    /// Whether the value is picked from a fixed set instead of typed.
+    pub fn is_selectable(&self) -> bool {
+        matches!(self, FieldValue::Choice { .. } | FieldValue::Toggle(_))
+    }
+
+    /// What a choice or a toggle can be set to, with the index of the current value.
+    pub fn options(&self) -> Option<(Vec<String>, usize)> {
+        match self {
+            FieldValue::Choice { options, selected } => Some((options.clone(), *selected)),
+            FieldValue::Toggle(on) =>
+                Some((vec![String::from("false"), String::from("true")], *on as usize)),
+            _ => None,
+        }
+    }
+
+    /// Moves a choice to the next (`forward`) or previous option, wrapping
+    /// around, and flips a toggle. Other values stay as they are.
+    pub fn cycle(&mut self, forward: bool) {
+        match self {
+            FieldValue::Choice { options, selected } if !options.is_empty() => {
+                *selected = match forward {
+                    true => (*selected + 1) % options.len(),
+                    false => (*selected + options.len() - 1) % options.len(),
+                };
+            }
+            FieldValue::Toggle(on) => {
+                *on = !*on;
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks `input` against the bounds of this value and returns the value it
+    /// describes.
+    fn parse(&self, label: &str, input: &str) -> Result<FieldValue, String> {
+        match self {
+            FieldValue::Text { required, .. } => {
+                if *required && input.trim().is_empty() {
+                    return Err(format!("{} cannot be empty.", label));
+                }
+                Ok(FieldValue::text(input, *required))
+            }
+            FieldValue::Integer { min, max, .. } =>
+                input
+                    .trim()
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|value| (*min..=*max).contains(value))
+                    .map(|value| FieldValue::integer(value, *min, *max))
+                    .ok_or_else(|| format!("Enter a number from {} to {}.", min, max)),
+            FieldValue::Choice { options, .. } =>
+                match options.iter().position(|option| option == input.trim()) {
+                    Some(selected) => Ok(FieldValue::Choice { options: options.clone(), selected }),
+                    None => Err(format!("Choose one of: {}.", options.join(", "))),
+                }
+            FieldValue::Toggle(_) =>
+                match input.trim() {
+                    "true" => Ok(FieldValue::Toggle(true)),
+                    "false" => Ok(FieldValue::Toggle(false)),
+                    _ => Err(String::from("Enter true or false.")),
+                }
+            FieldValue::Path { must_exist, .. } => {
+                let path = input.trim();
+                if *must_exist && !path.is_empty() && !Path::new(path).exists() {
+                    return Err(format!("'{}' does not exist.", path));
+                }
+                Ok(FieldValue::Path { path: path.to_string(), must_exist: *must_exist })
+            }
+            FieldValue::Commands(_) => {
+                let commands = FieldValue::commands(input);
+                if let FieldValue::Commands(list) = &commands {
+                    if let Some(empty) = list.iter().position(|command| command.trim().is_empty()) {
+                        return Err(format!("Command #{} is empty.", empty + 1));
+                    }
+                }
+                Ok(commands)
+            }
+        }
+    }
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldValue::Text { text, .. } => write!(f, "{}", text),
+            FieldValue::Integer { value, .. } => write!(f, "{}", value),
+            FieldValue::Choice { options, selected } =>
+                write!(f, "{}", options.get(*selected).map(String::as_str).unwrap_or_default()),
+            FieldValue::Toggle(on) => write!(f, "{}", on),
+            FieldValue::Path { path, .. } => write!(f, "{}", path),
+            FieldValue::Commands(commands) => write!(f, "{}", commands.join(",")),
+        }
+    }
+}
+
+impl FormField {
+    pub fn new(target: FieldTarget, label: &str, value: FieldValue) -> FormField {
+        FormField { target, label: label.to_string(), value, error: None }
+    }
+
+    pub fn get_target(&self) -> FieldTarget {
+        self.target
+    }
+
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn get_value(&self) -> &FieldValue {
+        &self.value
+    }
+
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The line shown in the list, e.g. `Tabs: 3`.
+    pub fn item_name(&self) -> String {
+        format!("{}: {}", self.label, self.value)
+    }
+
+    /// Validates `input` and keeps it as the new value. A rejected input leaves
+    /// the value alone and is explained by `get_error`.
+    pub fn submit(&mut self, input: &str) -> Result<(), String> {
+        match self.value.parse(&self.label, input) {
+            Ok(value) => {
+                self.value = value;
+                self.error = None;
+                Ok(())
+            }
+            Err(err) => {
+                self.error = Some(err.clone());
+                Err(err)
+            }
+        }
+    }
+
+    /// Cycles a choice or a toggle, see `FieldValue::cycle`.
+    pub fn cycle(&mut self, forward: bool) {
+        self.value.cycle(forward);
+        self.error = None;
+    }
+
+    /// Reports `error` for this field, for checks that need more than the value,
+    /// such as a preset name already being taken.
+    pub fn reject(&mut self, error: &str) {
+        self.error = Some(error.to_string());
+    }
+}
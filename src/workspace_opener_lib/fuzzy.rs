@@ -0,0 +1,108 @@
+const MATCH_SCORE: i64 = 16;
+const BOUNDARY_BONUS: i64 = 8;
+const CONSECUTIVE_BONUS: i64 = 12;
+const GAP_PENALTY: i64 = 1;
+
+/// Result of matching a pattern against a text. `indices` are char indices of
+/// the matched characters in the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub(super) score: i64,
+    pub(super) indices: Vec<usize>,
+}
+
+impl FuzzyMatch {
+    pub fn get_score(&self) -> i64 {
+        self.score
+    }
+
+    pub fn get_indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+/// Case-insensitive subsequence match. Matches at word starts and runs of
+/// consecutive characters score higher, gaps between matched characters lower.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern = pattern
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .flat_map(|ch| ch.to_lowercase())
+        .collect::<Vec<char>>();
+    let text_chars = text.chars().collect::<Vec<char>>();
+    let lowered = text_chars
+        .iter()
+        .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect::<Vec<char>>();
+
+    if pattern.is_empty() {
+        return Some(FuzzyMatch { score: 0, indices: vec![] });
+    }
+    if pattern.len() > lowered.len() {
+        return None;
+    }
+
+    let n = lowered.len();
+    // best[i][j]: best score with pattern[i] matched at text[j], prev[i][j] the text index of pattern[i - 1].
+    let mut best = vec![vec![None::<i64>; n]; pattern.len()];
+    let mut prev = vec![vec![0usize; n]; pattern.len()];
+
+    for (i, pattern_ch) in pattern.iter().enumerate() {
+        for j in 0..n {
+            if lowered[j] != *pattern_ch {
+                continue;
+            }
+            let char_score = MATCH_SCORE + boundary_bonus(&text_chars, j);
+
+            if i == 0 {
+                best[i][j] = Some(char_score - (j as i64) * GAP_PENALTY);
+                continue;
+            }
+
+            for k in 0..j {
+                let Some(previous) = best[i - 1][k] else {
+                    continue;
+                };
+                let transition = match k + 1 == j {
+                    true => CONSECUTIVE_BONUS,
+                    false => -((j - k - 1) as i64) * GAP_PENALTY,
+                };
+                let score = previous + char_score + transition;
+                if best[i][j].is_none_or(|current| score > current) {
+                    best[i][j] = Some(score);
+                    prev[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let last = pattern.len() - 1;
+    let (mut j, score) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|(j, score)| (*score, std::cmp::Reverse(*j)))?;
+
+    let mut indices = vec![j; pattern.len()];
+    for i in (1..pattern.len()).rev() {
+        j = prev[i][j];
+        indices[i - 1] = j;
+    }
+
+    Some(FuzzyMatch { score, indices })
+}
+
+fn boundary_bonus(text: &[char], index: usize) -> i64 {
+    if index == 0 {
+        return BOUNDARY_BONUS;
+    }
+
+    let (previous, current) = (text[index - 1], text[index]);
+    let after_separator = !previous.is_alphanumeric();
+    let camel_case = previous.is_lowercase() && current.is_uppercase();
+
+    match after_separator || camel_case {
+        true => BOUNDARY_BONUS,
+        false => 0,
+    }
+}
@@ -0,0 +1,224 @@
+use super::{ app::{ InputMode, State }, keymap::Keymap, model::Action };
+
+/// A titled group of rows in the help overlay. Every row is the keys bound to
+/// an action and what the action does in the current screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HelpSection {
+    pub(super) title: &'static str,
+    pub(super) entries: Vec<(String, String)>,
+}
+
+impl HelpSection {
+    pub fn get_title(&self) -> &str {
+        self.title
+    }
+
+    pub fn get_entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+}
+
+/// Everything that can be done in `state` with `input_mode`, with the keys bound
+/// in `keymap`. Actions without keys are left out, as are sections left empty.
+pub fn help_sections(keymap: &Keymap, state: State, input_mode: &InputMode) -> Vec<HelpSection> {
+    let typing = !matches!(input_mode, InputMode::Normal | InputMode::Select);
+
+    let launch = match state {
+        State::ChoosePreset => "run the selected preset",
+        State::EditPreset => "edit the selected field",
+        State::Trash => "restore the selected preset",
+        _ => "choose the selected item",
+    };
+
+    let groups: Vec<(&'static str, Vec<(Action, &str)>)> = match input_mode {
+        InputMode::Normal if state == State::LayoutEditor =>
+            vec![
+                (
+                    "Layout",
+                    vec![
+                        (Action::SplitVertical, "split the focused pane side by side"),
+                        (Action::SplitHorizontal, "split the focused pane into two stacked ones"),
+                        (Action::Grow, "grow the focused pane"),
+                        (Action::Shrink, "shrink the focused pane"),
+                        (Action::Delete, "delete the focused pane with its command"),
+                        (Action::Up, "focus the pane above"),
+                        (Action::Down, "focus the pane below"),
+                        (Action::Left, "focus the pane on the left"),
+                        (Action::Right, "focus the pane on the right"),
+                        (Action::Launch, "save the layout"),
+                        (Action::Back, "discard the changes")
+                    ],
+                ),
+                ("General", vec![(Action::Help, "show this help")])
+            ],
+        InputMode::Select =>
+            vec![
+                (
+                    "Value",
+                    vec![
+                        (Action::Toggle, "pick the next value"),
+                        (Action::Down, "pick the next value"),
+                        (Action::Up, "pick the previous value"),
+                        (Action::Right, "pick the next value"),
+                        (Action::Left, "pick the previous value"),
+                        (Action::Launch, "save the field"),
+                        (Action::Back, "discard the change")
+                    ],
+                ),
+                ("General", vec![(Action::Help, "show this help")])
+            ],
+        InputMode::Normal => {
+            let mut groups = vec![
+                (
+                    "Navigation",
+                    vec![
+                        (Action::Up, "move up"),
+                        (Action::Down, "move down"),
+                        (Action::First, "jump to the first item"),
+                        (Action::Last, "jump to the last item"),
+                        (Action::Launch, launch),
+                        (Action::Back, "go back")
+                    ],
+                )
+            ];
+            if state == State::EditPreset || state == State::Settings {
+                groups.push((
+                    "Fields",
+                    vec![(Action::Toggle, "flip the selected switch or pick its next value")],
+                ));
+            }
+            if state == State::ChoosePreset {
+                groups.push((
+                    "Presets",
+                    vec![
+                        (Action::Edit, "edit the selected preset"),
+                        (Action::Delete, "move the selected preset to the trash"),
+                        (Action::Search, "search names, tags and commands")
+                    ],
+                ));
+            }
+            if state == State::EditPreset {
+                groups.push((
+                    "Tabs and windows",
+                    vec![
+                        (Action::InsertBefore, "add an empty tab/window before the selected one"),
+                        (Action::InsertAfter, "add an empty tab/window after the selected one"),
+                        (Action::Duplicate, "copy the selected tab/window with its commands"),
+                        (Action::Delete, "delete the selected tab/window with its commands"),
+                        (Action::MoveLeft, "move the selected tab/window left"),
+                        (Action::MoveRight, "move the selected tab/window right"),
+                        (Action::Layout, "arrange the windows of the selected tab")
+                    ],
+                ));
+            }
+            if state == State::Trash {
+                groups.push(("Trash", vec![(Action::Delete, "delete the selected preset for good")]));
+            }
+            groups.push(("General", vec![(Action::Help, "show this help"), (Action::Quit, "exit")]));
+            groups
+        }
+        _ => {
+            let (submit, cancel) = match input_mode {
+                InputMode::Input => ("confirm the answer", "cancel creating the preset"),
+                InputMode::Edit => ("save the field", "discard the change"),
+                _ => (launch, "close the filter"),
+            };
+            let mut input = vec![(Action::Submit, submit), (Action::Cancel, cancel)];
+            if matches!(input_mode, InputMode::Input) {
+                input.push((Action::PreviousStep, "go back to the previous question"));
+                input.push((Action::Up, "pick the previous choice"));
+                input.push((Action::Down, "pick the next choice"));
+            }
+            if matches!(input_mode, InputMode::Filter) {
+                input.push((Action::Up, "move to the previous match"));
+                input.push((Action::Down, "move to the next match"));
+            }
+
+            vec![
+                ("Input", input),
+                (
+                    "Cursor",
+                    vec![
+                        (Action::Left, "move left"),
+                        (Action::Right, "move right"),
+                        (Action::WordLeft, "jump to the previous word"),
+                        (Action::WordRight, "jump to the next word"),
+                        (Action::Home, "jump to the start"),
+                        (Action::End, "jump to the end")
+                    ],
+                ),
+                (
+                    "Editing",
+                    vec![
+                        (Action::Backspace, "delete the selection or the previous character"),
+                        (Action::DeleteForward, "delete the selection or the next character"),
+                        (Action::DeleteWordLeft, "delete the previous word"),
+                        (Action::DeleteWordRight, "delete the next word"),
+                        (Action::Undo, "undo"),
+                        (Action::Redo, "redo")
+                    ],
+                ),
+                (
+                    "Selection",
+                    vec![
+                        (Action::SelectLeft, "extend the selection to the left"),
+                        (Action::SelectRight, "extend the selection to the right"),
+                        (Action::SelectAll, "select everything"),
+                        (Action::Copy, "copy the selection"),
+                        (Action::Cut, "cut the selection"),
+                        (Action::Paste, "paste from the clipboard")
+                    ],
+                ),
+                ("General", vec![(Action::Help, "show this help")])
+            ]
+        }
+    };
+
+    let mut sections = groups
+        .into_iter()
+        .map(|(title, actions)| HelpSection {
+            title,
+            entries: actions
+                .into_iter()
+                .filter_map(|(action, description)| {
+                    let keys = keymap.labels_for(action, typing);
+                    match keys.is_empty() {
+                        true => None,
+                        false => Some((keys.join(" / "), description.to_string())),
+                    }
+                })
+                .collect(),
+        })
+        .filter(|section| !section.entries.is_empty())
+        .collect::<Vec<HelpSection>>();
+
+    let mouse = match input_mode {
+        InputMode::Normal if state == State::LayoutEditor => vec![],
+        InputMode::Select => vec![("scroll", "pick the next or previous value")],
+        InputMode::Normal if state == State::Trash =>
+            vec![("click", "select an item"), ("scroll", "move through the list")],
+        InputMode::Normal =>
+            vec![
+                ("click", "select an item"),
+                ("double click", launch),
+                ("scroll", "move through the list")
+            ],
+        InputMode::Input => vec![("click", "place the cursor in the input box")],
+        _ =>
+            vec![
+                ("click", "place the cursor in the input box"),
+                ("scroll", "move through the list")
+            ],
+    };
+    if !mouse.is_empty() {
+        sections.push(HelpSection {
+            title: "Mouse",
+            entries: mouse
+                .into_iter()
+                .map(|(keys, description)| (keys.to_string(), description.to_string()))
+                .collect(),
+        });
+    }
+
+    sections
+}
@@ -1,146 +1,38 @@
 use super::{
     model::{
-        App,
         AppConfig,
-        InputMode,
-        Item,
-        Popup,
         Preset,
-        PresetCreationHelper,
         PresetValue,
         PresetInfo,
-        State,
-        StatefulList,
         ShellType,
+        StructureEdit,
         PresetInfoValue,
         Settings,
+        LaunchPlan,
+        LogLevel,
+        LogSettings,
+        KeymapSettings,
+        Launcher,
+        ThemeSettings,
+        TrashedPreset,
         WriteType,
+        BUILT_IN_THEMES,
     },
-    api::CONFIG,
+    form::{ FieldTarget, FieldValue, FormField },
+    fuzzy::{ self, FuzzyMatch },
+    layout::{ LayoutEditor, PaneLayout, SplitDirection },
 };
-use crossterm::event::KeyCode;
 use log::error;
-use std::{ collections::VecDeque, fmt::Display };
-use tui::{ style::Color, widgets::ListState };
-use std::{ fs::File, io::{ BufWriter, Write } };
-impl Item {
-    pub fn new(name: String, leading_state: State, preset_value: Option<PresetValue>) -> Item {
-        Item {
-            name,
-            leading_state,
-            preset_value,
-        }
-    }
-}
-
-impl State {
-    fn create_items(&self, app_config: Option<&AppConfig>) -> Option<Vec<Item>> {
-        match self {
-            State::Start =>
-                Some(
-                    vec![
-                        Item::new("Choose Preset".to_string(), State::ChoosePreset, None),
-                        Item::new("Create Preset".to_string(), State::CreatePreset, None),
-                        Item::new("Settings".to_string(), State::Settings, None)
-                    ]
-                ),
-            State::Settings =>
-                Some(
-                    vec![
-                        Item::new(
-                            format!("Debug mode: {}", app_config.unwrap().settings.debug_mode),
-                            State::ChangeFieldName,
-                            None
-                        )
-                    ]
-                ),
-            _ => None,
-        }
-    }
-
-    fn create_prompts(&self) -> Option<Vec<String>> {
-        match self {
-            State::CreatePreset =>
-                Some(
-                    vec!["Enter preset name:".to_string(), "Enter tabs amount (1-10):".to_string()]
-                ),
-            _ => None,
-        }
-    }
-}
-
-impl StatefulList {
-    fn with_items(items: Vec<Item>) -> Self {
-        let index = match items.len() {
-            0 => None,
-            _ => Some(0),
-        };
-
-        let mut list = StatefulList {
-            list_state: ListState::default(),
-            items,
-        };
-
-        list.list_state.select(index);
-        list
-    }
-
-    pub fn next(&mut self) {
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i >= self.items.len() - 1 { self.items.len() - 1 } else { i + 1 }
-            }
-            None => 0,
-        };
-
-        self.list_state.select(Some(i))
-    }
-
-    pub fn previous(&mut self) {
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i == 0 { 0 } else { i - 1 }
-            }
-            None => 0,
-        };
-
-        self.list_state.select(Some(i))
-    }
-
-    pub fn get_selected_item(&self) -> Option<Item> {
-        match self.list_state.selected() {
-            Some(i) =>
-                match self.items.get(i) {
-                    Some(i) => Some(i.clone()),
-                    None => None,
-                }
-            None => None,
-        }
-    }
-
-    pub fn get_selected_item_index(&self) -> Option<usize> {
-        match self.list_state.selected() {
-            Some(i) => Some(i),
-            None => None,
-        }
-    }
-
-    pub fn delete_selected_item(&mut self) -> Result<(), String> {
-        match self.list_state.selected() {
-            Some(i) => {
-                self.items.remove(i);
-            }
-            None => {
-                return Err(String::from("Cannot delete item: ITEM WITH THIS INDEX WAS NOT FOUND."));
-            }
-        }
-        Ok(())
-    }
-}
-
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    str::FromStr,
+    time::{ SystemTime, UNIX_EPOCH },
+};
+use std::{ fs::{ self, File }, io::{ BufWriter, Write }, path::{ Path, PathBuf } };
 impl Preset {
-    pub fn from_input(input: &Vec<String>) -> Self {
-        let name = input.get(0).unwrap().to_string();
+    pub fn from_input(input: &[String]) -> Self {
+        let name = input.first().unwrap().to_string();
         let tabs = input.get(1).unwrap().parse::<u8>().expect("Failed to parse tabs arg.");
         let windows = input
             .iter()
@@ -165,6 +57,8 @@ impl Preset {
             windows,
             args,
             preset_info,
+            tags: Vec::new(),
+            layouts: Vec::new(),
         }
     }
 
@@ -175,7 +69,7 @@ impl Preset {
         args: Vec<String>,
         preset_info: PresetInfo
     ) -> Preset {
-        Preset { name, tabs, windows, args, preset_info }
+        Preset { name, tabs, windows, args, preset_info, tags: Vec::new(), layouts: Vec::new() }
     }
 
     pub fn change_field_value(&mut self, preset_value: PresetValue) -> Result<(), String> {
@@ -207,13 +101,14 @@ impl Preset {
                     }
                 }
                 self.tabs = new_tabs;
+                self.tidy_layouts();
             }
             PresetValue::Windows(index, new_windows) => {
                 if new_windows == 0 {
                     return Err(String::from("Windows cannot have a value of 0."));
                 }
                 let args_before = self.windows[0..index].iter().sum::<u8>();
-                let Some(old_windows) = self.windows.get_mut(index as usize) else {
+                let Some(old_windows) = self.windows.get_mut(index) else {
                     return Err(String::from("Cannot find windows with current index."));
                 };
 
@@ -231,14 +126,18 @@ impl Preset {
                 }
 
                 *old_windows = new_windows;
+                self.set_layout(index, None);
             }
 
             PresetValue::Args(index, new_name) => {
-                let Some(current_arg) = self.args.get_mut(index as usize) else {
+                let Some(current_arg) = self.args.get_mut(index) else {
                     return Err(String::from("Cannot find windows with current index."));
                 };
                 *current_arg = new_name;
             }
+            PresetValue::Tags(tags) => {
+                self.tags = tags;
+            }
             PresetValue::PresetInfo(new_preset_info_value) =>
                 match new_preset_info_value {
                     PresetInfoValue::WtProfile(new_wt_profile) => {
@@ -255,669 +154,514 @@ impl Preset {
 
         Ok(())
     }
-    pub fn into_items(&self) -> Vec<Item> {
-        let mut items = vec![];
-
-        let name = Item::new(
-            format!("Name: {}", self.name.as_str()),
-            State::ChangeFieldName,
-            Some(PresetValue::Name(self.name.to_string()))
-        );
 
-        let tabs = Item::new(
-            format!("Tabs: {}", &self.tabs.to_string()),
-            State::ChangeFieldName,
-            Some(PresetValue::Tabs(self.tabs))
-        );
-
-        items.push(name);
-        items.push(tabs);
+    /// Index in `args` of the first window of `tab`.
+    fn first_arg_of_tab(&self, tab: usize) -> usize {
+        self.windows[..tab].iter().map(|windows| *windows as usize).sum()
+    }
 
-        for (tab_index, windows_amount) in self.windows.iter().enumerate() {
-            let window = Item::new(
-                format!("Tab (#{}), windows: {}", tab_index + 1, &windows_amount.to_string()),
-                State::ChangeFieldName,
-                Some(PresetValue::Windows(tab_index, *windows_amount))
-            );
-            items.push(window);
+    /// The tab whose windows run `args[arg]`.
+    pub(super) fn tab_of_arg(&self, arg: usize) -> Option<usize> {
+        if arg >= self.args.len() {
+            return None;
         }
-
-        let mut window_index = 0;
-        let mut arg_count = 0;
-        for (arg_index, arg) in self.args.iter().enumerate() {
-            let Some(current_window) = self.windows.get(window_index) else {
-                error!(
-                    "Cannot create items from preset windows: INDEX OUT OF BOUNDS.\nWindows: {:?} \nIndex: {}",
-                    self.windows,
-                    window_index
-                );
-                panic!();
-            };
-
-            if arg_count >= *current_window {
-                if window_index + 1 < self.windows.len() {
-                    window_index += 1;
-                    arg_count = 0;
-                }
-            }
-            let arg = Item::new(
-                format!("Tab (#{}), window (#{}), Arg: {}", window_index + 1, arg_count + 1, arg),
-                State::ChangeFieldName,
-                Some(PresetValue::Args(arg_index, arg.to_string()))
-            );
-            items.push(arg);
-            arg_count += 1;
+        let mut tab = 0;
+        while self.first_arg_of_tab(tab + 1) <= arg {
+            tab += 1;
         }
-
-        let wt_profile = Item::new(
-            format!("Windows terminal profile name: {}", self.preset_info.wt_profile),
-            State::ChangeFieldName,
-            Some(
-                PresetValue::PresetInfo(
-                    PresetInfoValue::WtProfile(self.preset_info.wt_profile.clone())
-                )
-            )
-        );
-        items.push(wt_profile);
-
-        let init_shell = Item::new(
-            format!(
-                "Init shell (powershell/pwsh/cmd): {}",
-                self.preset_info.init_shell.as_string()
-            ),
-            State::ChangeFieldName,
-            Some(
-                PresetValue::PresetInfo(
-                    PresetInfoValue::InitShell(self.preset_info.init_shell.clone())
-                )
-            )
-        );
-        items.push(init_shell);
-
-        let target_shell = Item::new(
-            format!(
-                "Target shell (powershell/pwsh/cmd/bash/zsh/fish): {}",
-                self.preset_info.target_shell.as_string()
-            ),
-            State::ChangeFieldName,
-            Some(
-                PresetValue::PresetInfo(
-                    PresetInfoValue::TargetShell(self.preset_info.target_shell.clone())
-                )
-            )
-        );
-        items.push(target_shell);
-
-        items
+        Some(tab)
     }
 
-    pub fn default() -> Preset {
-        let preset_info = PresetInfo::default();
-        Preset {
-            name: String::from("Test Preset"),
-            tabs: 3,
-            windows: vec![2, 1, 1],
-            args: vec![
-                String::from("arg w1"),
-                String::from("arg w1"),
-                String::from("arg w2"),
-                String::from("arg w3")
-            ],
-            preset_info,
+    /// Changes the tabs around `tab` and returns the index of the tab to select
+    /// afterwards. The windows of a tab keep their commands wherever it goes.
+    pub fn edit_tab(&mut self, tab: usize, edit: StructureEdit) -> Result<usize, String> {
+        let Some(&windows) = self.windows.get(tab) else {
+            return Err(String::from("Cannot find a tab with given index."));
+        };
+        match edit {
+            StructureEdit::InsertBefore | StructureEdit::InsertAfter | StructureEdit::Duplicate
+                if self.windows.len() >= 10 => {
+                return Err(String::from("A preset cannot have more than 10 tabs."));
+            }
+            StructureEdit::Delete if self.windows.len() == 1 => {
+                return Err(String::from("A preset needs at least one tab."));
+            }
+            _ => {}
         }
-    }
-
-    pub fn get_preset_info(&self) -> PresetInfo {
-        self.preset_info.clone()
-    }
-}
+        let first_arg = self.first_arg_of_tab(tab);
+        let last_arg = first_arg + (windows as usize);
+        self.layouts.resize(self.windows.len(), None);
+
+        let selected = match edit {
+            StructureEdit::InsertBefore => {
+                self.args.insert(first_arg, String::new());
+                self.windows.insert(tab, 1);
+                self.layouts.insert(tab, None);
+                tab
+            }
+            StructureEdit::InsertAfter => {
+                self.args.insert(last_arg, String::new());
+                self.windows.insert(tab + 1, 1);
+                self.layouts.insert(tab + 1, None);
+                tab + 1
+            }
+            StructureEdit::Duplicate => {
+                let args = self.args[first_arg..last_arg].to_vec();
+                self.args.splice(last_arg..last_arg, args);
+                self.windows.insert(tab + 1, windows);
+                self.layouts.insert(tab + 1, self.layouts[tab].clone());
+                tab + 1
+            }
+            StructureEdit::Delete => {
+                self.args.drain(first_arg..last_arg);
+                self.windows.remove(tab);
+                self.layouts.remove(tab);
+                tab.min(self.windows.len() - 1)
+            }
+            StructureEdit::MoveLeft if tab > 0 => {
+                let previous = self.windows[tab - 1] as usize;
+                self.args[first_arg - previous..last_arg].rotate_left(previous);
+                self.windows.swap(tab - 1, tab);
+                self.layouts.swap(tab - 1, tab);
+                tab - 1
+            }
+            StructureEdit::MoveRight if tab + 1 < self.windows.len() => {
+                let next = self.windows[tab + 1] as usize;
+                self.args[first_arg..last_arg + next].rotate_left(windows as usize);
+                self.windows.swap(tab, tab + 1);
+                self.layouts.swap(tab, tab + 1);
+                tab + 1
+            }
+            StructureEdit::MoveLeft | StructureEdit::MoveRight => tab,
+        };
 
-impl Popup {
-    pub fn default() -> Self {
-        Popup {
-            active: false,
-            message: String::new(),
-            color: Color::White,
-        }
+        self.tabs = self.windows.len() as u8;
+        self.tidy_layouts();
+        Ok(selected)
     }
 
-    pub fn activate_popup(&mut self, message: &str, color: Color) {
-        self.active = true;
-        self.message = message.to_string();
-        self.color = color;
-    }
+    /// Changes the windows around the one whose command is `args[arg]`, staying
+    /// inside its tab, and returns the index of the command to select afterwards.
+    pub fn edit_window(&mut self, arg: usize, edit: StructureEdit) -> Result<usize, String> {
+        let Some(tab) = self.tab_of_arg(arg) else {
+            return Err(String::from("Cannot find a window with given index."));
+        };
+        let first_arg = self.first_arg_of_tab(tab);
+        let last_arg = first_arg + (self.windows[tab] as usize) - 1;
 
-    pub fn deactivate_popup(&mut self) {
-        self.active = false;
-    }
-}
+        let selected = match edit {
+            StructureEdit::InsertBefore | StructureEdit::InsertAfter | StructureEdit::Duplicate
+                if self.windows[tab] >= 4 => {
+                return Err(String::from("A tab cannot have more than 4 windows."));
+            }
+            StructureEdit::InsertBefore => {
+                self.args.insert(arg, String::new());
+                self.windows[tab] += 1;
+                arg
+            }
+            StructureEdit::InsertAfter => {
+                self.args.insert(arg + 1, String::new());
+                self.windows[tab] += 1;
+                arg + 1
+            }
+            StructureEdit::Duplicate => {
+                self.args.insert(arg + 1, self.args[arg].clone());
+                self.windows[tab] += 1;
+                arg + 1
+            }
+            StructureEdit::Delete => {
+                if self.windows[tab] == 1 {
+                    return Err(String::from("A tab needs at least one window, delete the tab instead."));
+                }
+                self.args.remove(arg);
+                self.windows[tab] -= 1;
+                arg.min(last_arg - 1)
+            }
+            StructureEdit::MoveLeft if arg > first_arg => {
+                self.args.swap(arg - 1, arg);
+                self.swap_layout_panes(tab, arg - 1 - first_arg, arg - first_arg);
+                arg - 1
+            }
+            StructureEdit::MoveRight if arg < last_arg => {
+                self.args.swap(arg, arg + 1);
+                self.swap_layout_panes(tab, arg - first_arg, arg + 1 - first_arg);
+                arg + 1
+            }
+            StructureEdit::MoveLeft | StructureEdit::MoveRight => {
+                return Ok(arg);
+            }
+        };
 
-impl App {
-    pub fn default() -> App {
-        App {
-            state: State::Start,
-            previous_state: State::Start,
-            items: StatefulList::with_items(State::Start.create_items(None).unwrap()),
-            prompts: Vec::new(),
-            input: String::new(),
-            selected_input: Vec::new(),
-            cursor_idx: 0,
-            input_mode: InputMode::Normal,
-            messages: Vec::new(),
-            popup: Popup::default(),
-            current_preset: None,
-            debug_mode: false,
+        // Moved windows keep their place in the layout, new or deleted ones
+        // go back to the default arrangement.
+        if !matches!(edit, StructureEdit::MoveLeft | StructureEdit::MoveRight) {
+            self.set_layout(tab, None);
         }
-    }
-    pub fn get_state(&self) -> State {
-        self.state.clone()
+        Ok(selected)
     }
 
-    pub fn select_left_input(&mut self, cursor_idx: usize) -> () {
-        let selected_input = &mut self.selected_input;
-        match selected_input.last() {
-            Some(last) if *last == cursor_idx - 1 => {
-                selected_input.pop();
-            }
-            _ => {
-                selected_input.insert(0, cursor_idx - 1);
-            }
+    /// The arrangement of the windows of `tab`: the one drawn in the layout
+    /// editor when it still fits the windows and its ratios are in range,
+    /// otherwise the default one.
+    pub fn layout(&self, tab: usize) -> PaneLayout {
+        let windows = self.windows.get(tab).copied().unwrap_or(1);
+
+        match self.layouts.get(tab) {
+            Some(Some(layout)) if layout.fits(windows) && layout.ratios_in_range() => layout.clone(),
+            _ => PaneLayout::default_for(windows),
         }
     }
 
-    pub fn select_right_input(&mut self, cursor_idx: usize) -> () {
-        let selected_input = &mut self.selected_input;
-        match selected_input.iter().find(|pos| **pos == cursor_idx) {
-            Some(_) => {
-                selected_input.remove(0);
-            }
-            _ => {
-                selected_input.push(cursor_idx);
-            }
+    fn set_layout(&mut self, tab: usize, layout: Option<PaneLayout>) {
+        if self.layouts.len() <= tab {
+            self.layouts.resize(tab + 1, None);
         }
+        self.layouts[tab] = layout;
+        self.tidy_layouts();
     }
 
-    pub fn move_cursor_left(&mut self) {
-        let current_point = self.cursor_idx;
-        if current_point == 0 {
-            return;
+    fn swap_layout_panes(&mut self, tab: usize, a: usize, b: usize) {
+        if let Some(Some(layout)) = self.layouts.get_mut(tab) {
+            layout.swap_panes(a, b);
         }
-        self.cursor_idx = current_point - 1;
-        self.selected_input.clear();
     }
 
-    pub fn move_cursor_right(&mut self) {
-        let current_point = self.cursor_idx;
-        if current_point >= self.input.len() {
-            return;
+    /// Drops layouts of tabs that no longer exist and trailing default ones, so a
+    /// preset without drawn layouts has none saved.
+    fn tidy_layouts(&mut self) {
+        self.layouts.truncate(self.windows.len());
+        while let Some(None) = self.layouts.last() {
+            self.layouts.pop();
         }
-        self.cursor_idx = current_point + 1;
-
-        self.selected_input.clear();
     }
 
-    pub fn insert_char(&mut self, ch: char) {
-        let cursor_idx = self.cursor_idx;
-        let input = &mut self.input;
-        let selected_input = &mut self.selected_input;
+    /// Saves the tab arranged in `editor`: its windows, their commands and the
+    /// layout. A layout equal to the default one is not stored.
+    pub fn apply_layout(&mut self, editor: &LayoutEditor) -> Result<(), String> {
+        let Some(first_arg) = self.get_arg_index(editor.tab, 0) else {
+            return Err(String::from("Cannot find a tab with given index."));
+        };
+        let windows = self.windows[editor.tab] as usize;
 
-        if !selected_input.is_empty() {
-            let first = *selected_input.first().unwrap();
-            let last = *selected_input.last().unwrap();
+        self.args.splice(first_arg..first_arg + windows, editor.args.iter().cloned());
+        self.windows[editor.tab] = editor.args.len() as u8;
 
-            input.drain(first..last + 1);
-            input.insert(first, ch);
-            selected_input.clear();
-            let new_idx = std::cmp::min(first + 1, input.len());
-            self.cursor_idx = new_idx;
-        } else {
-            input.insert(cursor_idx, ch);
-            self.cursor_idx = cursor_idx + 1;
-        }
+        let layout = match editor.layout == PaneLayout::default_for(editor.args.len() as u8) {
+            true => None,
+            false => Some(editor.layout.clone()),
+        };
+        self.set_layout(editor.tab, layout);
+        Ok(())
     }
 
-    pub fn delete_characters(&mut self) {
-        let cursor_idx = self.cursor_idx;
-        let selected_input_len = self.selected_input.len();
+    /// The editable fields of the preset, in the order Edit Preset lists them.
+    pub fn form_fields(&self) -> Vec<FormField> {
+        let mut fields = vec![
+            FormField::new(FieldTarget::Name, "Name", FieldValue::text(&self.name, true)),
+            FormField::new(FieldTarget::Tabs, "Tabs", FieldValue::integer(self.tabs as i64, 1, 10))
+        ];
 
-        if selected_input_len > 0 {
-            let last_selected = *self.selected_input.last().unwrap();
+        for (tab_index, windows_amount) in self.windows.iter().enumerate() {
+            fields.push(
+                FormField::new(
+                    FieldTarget::Windows(tab_index),
+                    &format!("Tab (#{}), windows", tab_index + 1),
+                    FieldValue::integer(*windows_amount as i64, 1, 4)
+                )
+            );
+        }
 
-            let mut chars: Vec<char> = self.input.chars().collect();
-            for &index in self.selected_input.iter().rev() {
-                chars.remove(index);
+        let mut window_index = 0;
+        let mut arg_count = 0;
+        for (arg_index, arg) in self.args.iter().enumerate() {
+            while self.windows.get(window_index).is_some_and(|windows| arg_count >= *windows) {
+                window_index += 1;
+                arg_count = 0;
             }
-            self.input = chars.into_iter().collect();
-            self.selected_input.clear();
-
-            if cursor_idx >= last_selected {
-                self.cursor_idx = cursor_idx - selected_input_len;
+            // Args past the last window get no field, validate() reports them.
+            if window_index >= self.windows.len() {
+                break;
             }
-            return;
-        }
-
-        if cursor_idx == 0 {
-            return;
+            fields.push(
+                FormField::new(
+                    FieldTarget::Args(arg_index),
+                    &format!("Tab (#{}), window (#{}), Arg", window_index + 1, arg_count + 1),
+                    FieldValue::commands(arg)
+                )
+            );
+            arg_count += 1;
         }
 
-        self.input.remove(cursor_idx - 1);
-        self.cursor_idx = cursor_idx - 1;
-    }
+        let init_shells = ShellType::init_names();
 
-    fn move_cursor_left_word_pos(input: &str, cursor_idx: usize) -> usize {
-        if cursor_idx == 0 {
-            return cursor_idx;
-        }
+        fields.push(
+            FormField::new(
+                FieldTarget::WtProfile,
+                "Windows terminal profile name",
+                FieldValue::text(&self.preset_info.wt_profile, false)
+            )
+        );
+        fields.push(
+            FormField::new(
+                FieldTarget::InitShell,
+                "Init shell (powershell/pwsh/cmd)",
+                FieldValue::choice(init_shells, &self.preset_info.init_shell.as_string())
+            )
+        );
+        fields.push(
+            FormField::new(
+                FieldTarget::TargetShell,
+                "Target shell (powershell/pwsh/cmd/bash/zsh/fish)",
+                FieldValue::choice(ShellType::names(), &self.preset_info.target_shell.as_string())
+            )
+        );
+        fields.push(
+            FormField::new(
+                FieldTarget::Tags,
+                "Tags (comma separated)",
+                FieldValue::text(&self.tags.join(", "), false)
+            )
+        );
 
-        let mut chars = input[..cursor_idx].char_indices().rev().peekable();
+        fields
+    }
 
-        while let Some(first) = chars.next() {
-            if !first.1.is_whitespace() {
-                if let Some(next) = chars.peek() {
-                    if next.1.is_whitespace() {
-                        return first.0;
-                    }
-                }
+    /// Stores the value of a field from `form_fields`.
+    pub fn apply_field(&mut self, field: &FormField) -> Result<(), String> {
+        let value = field.get_value();
+        let text = value.to_string();
+
+        let preset_value = match field.get_target() {
+            FieldTarget::Name => PresetValue::Name(text),
+            FieldTarget::Tabs => PresetValue::Tabs(value.as_integer().unwrap_or(0) as u8),
+            FieldTarget::Windows(tab) =>
+                PresetValue::Windows(tab, value.as_integer().unwrap_or(0) as u8),
+            FieldTarget::Args(arg) => PresetValue::Args(arg, text),
+            FieldTarget::WtProfile => PresetValue::PresetInfo(PresetInfoValue::WtProfile(text)),
+            FieldTarget::InitShell =>
+                PresetValue::PresetInfo(PresetInfoValue::InitShell(text.parse::<ShellType>()?)),
+            FieldTarget::TargetShell =>
+                PresetValue::PresetInfo(PresetInfoValue::TargetShell(text.parse::<ShellType>()?)),
+            FieldTarget::Tags =>
+                PresetValue::Tags(
+                    text
+                        .split(',')
+                        .map(|tag| tag.trim())
+                        .filter(|tag| !tag.is_empty())
+                        .map(|tag| tag.to_string())
+                        .collect()
+                ),
+            _ => {
+                return Err(String::from("Cannot edit preset: NOT A PRESET FIELD."));
             }
-        }
+        };
 
-        return 0;
+        self.change_field_value(preset_value)
     }
 
-    fn move_cursor_right_word_pos(input: &str, cursor_idx: usize) -> usize {
-        if cursor_idx >= input.len() {
-            return input.len();
-        }
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
 
-        let mut chars = input[cursor_idx..].char_indices().peekable();
+    pub fn get_tabs(&self) -> u8 {
+        self.tabs
+    }
 
-        while let Some(first) = chars.next() {
-            if first.1.is_whitespace() {
-                if let Some(next) = chars.peek() {
-                    if !next.1.is_whitespace() {
-                        return next.0 + cursor_idx;
-                    }
-                }
-            }
-        }
-        let last_char = input
-            .chars()
-            .into_iter()
-            .rev()
-            .position(|ch| ch.is_ascii_alphabetic());
+    /// Window count of every tab.
+    pub fn get_windows(&self) -> &[u8] {
+        &self.windows
+    }
 
-        if let Some(pos) = last_char {
-            return std::cmp::max(input.len() - pos, 0);
-        } else {
-            return input.len();
-        }
+    /// Commands of all windows, tab after tab. `get_tab_args` splits them up.
+    pub fn get_args(&self) -> &[String] {
+        &self.args
     }
 
-    pub fn handle_control_key_action(&mut self, key_code: KeyCode) {
-        match key_code {
-            KeyCode::Char('c') => {
-                let selected_input = &self.selected_input;
-                if selected_input.is_empty() {
-                    return;
-                }
-                let first = *selected_input.first().unwrap();
-                let last = *selected_input.last().unwrap();
+    pub fn get_preset_info(&self) -> PresetInfo {
+        self.preset_info.clone()
+    }
 
-                let input = &self.input;
-                let input = &input[first..=last];
-                if let Err(err) = cli_clipboard::set_contents(input.to_string()) {
-                    error!("Something went wrong while trying to copy: {:?}", err);
-                }
-            }
-            KeyCode::Char('v') => {
-                if let Ok(cli_contents) = cli_clipboard::get_contents() {
-                    let cursor_idx = self.cursor_idx;
-                    let input = &mut self.input;
-
-                    if !self.selected_input.is_empty() {
-                        let first = *self.selected_input.first().unwrap();
-                        let last = *self.selected_input.last().unwrap();
-
-                        input.drain(first..last + 1);
-                        self.selected_input.clear();
-                    } else {
-                        input.insert_str(cursor_idx, &cli_contents);
-                    }
+    /// Lists everything that would stop the preset from being opened correctly.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = vec![];
 
-                    let new_index = cursor_idx + 1 + cli_contents.len();
-                    self.cursor_idx = new_index;
-                }
-            }
-            KeyCode::Left => {
-                let cursor_idx = self.cursor_idx;
-                self.selected_input.clear();
-                let new_index = App::move_cursor_left_word_pos(&self.input, cursor_idx);
-                self.cursor_idx = new_index;
-            }
-            KeyCode::Right => {
-                let cursor_idx = self.cursor_idx;
-                self.selected_input.clear();
-                let new_index = App::move_cursor_right_word_pos(&self.input, cursor_idx);
-                self.cursor_idx = new_index;
+        if self.name.trim().is_empty() {
+            problems.push(String::from("Name cannot be empty."));
+        }
+        if !(1..=10).contains(&self.tabs) {
+            problems.push(format!("Tabs must be between 1 and 10, found {}.", self.tabs));
+        }
+        if self.windows.len() != (self.tabs as usize) {
+            problems.push(
+                format!(
+                    "Expected windows for {} tabs, found {}.",
+                    self.tabs,
+                    self.windows.len()
+                )
+            );
+        }
+        for (tab_index, windows) in self.windows.iter().enumerate() {
+            if !(1..=4).contains(windows) {
+                problems.push(
+                    format!(
+                        "Tab (#{}) windows must be between 1 and 4, found {}.",
+                        tab_index + 1,
+                        windows
+                    )
+                );
             }
-            _ => {}
         }
-    }
-
-    pub fn handle_shift_key_action(&mut self, key_code: KeyCode) {
-        match key_code {
-            KeyCode::Char(ch) => self.insert_char(ch),
-            KeyCode::Left => {
-                let cursor_idx = self.cursor_idx;
 
-                if cursor_idx == 0 {
-                    return;
+        for (tab_index, layout) in self.layouts.iter().enumerate() {
+            let windows = self.windows.get(tab_index).copied().unwrap_or(0);
+            if let Some(layout) = layout {
+                if !layout.fits(windows) {
+                    problems.push(
+                        format!(
+                            "Tab (#{}) layout does not match its {} windows.",
+                            tab_index + 1,
+                            windows
+                        )
+                    );
                 }
-
-                let new_idx = cursor_idx - 1;
-                self.select_left_input(cursor_idx);
-                self.cursor_idx = new_idx;
-            }
-            KeyCode::Right => {
-                let cursor_idx = self.cursor_idx;
-                let input = self.input.as_str();
-
-                if cursor_idx >= input.len() {
-                    return;
+                if !layout.ratios_in_range() {
+                    problems.push(
+                        format!("Tab (#{}) layout split ratios must be between 10 and 90.", tab_index + 1)
+                    );
                 }
-
-                let new_idx = cursor_idx + 1;
-                self.select_right_input(cursor_idx);
-                self.cursor_idx = new_idx;
             }
-            _ => {}
         }
-    }
-
-    fn create_preset_value(&mut self, pch: &mut PresetCreationHelper) -> Result<(), ()> {
-        let msg_length = self.messages.len();
-        if msg_length == 1 {
-            let Ok(tabs_amount) = self.input.parse::<u8>() else {
-                return Err(());
-            };
-            if tabs_amount < 1 || tabs_amount > 10 {
-                return Err(());
-            }
-
-            for n in 1..=tabs_amount {
-                self.prompts.push(format!("Enter windows amount (1-4) for tab number {}: ", n));
-            }
-            pch.max_windows = tabs_amount as usize;
-        } else if msg_length > 1 && pch.windows.len() < pch.max_windows {
-            let Ok(input) = self.input.parse::<u8>() else {
-                return Err(());
-            };
-            if input < 1 || input > 4 {
-                return Err(());
-            }
-            pch.windows.push_back(input);
 
-            let Some(&windows_back) = pch.windows.back() else {
-                return Err(());
-            };
-            for n in 1..=windows_back {
-                self.prompts.push(format!("Enter arg {} for window {}", n, pch.windows.len()));
-            }
+        let total_windows = self.windows
+            .iter()
+            .map(|w| *w as usize)
+            .sum::<usize>();
+        if self.args.len() != total_windows {
+            problems.push(
+                format!("Expected {} args (one per window), found {}.", total_windows, self.args.len())
+            );
         }
 
-        Ok(())
-    }
-
-    fn format_input(input: &str) -> String {
-        let mut input = input.trim();
-        if input.ends_with(",") {
-            input = &input[..input.len() - 1];
+        if !ShellType::INIT.contains(&self.preset_info.init_shell) {
+            let names = ShellType::init_names();
+            let last = names.len() - 1;
+            problems.push(
+                format!(
+                    "Init shell must be {} or {}, found {}.",
+                    names[..last].join(", "),
+                    names[last],
+                    self.preset_info.init_shell
+                )
+            );
         }
 
-        input.to_string()
+        problems
     }
 
-    pub fn handle_creating_preset(
-        &mut self,
-        pch: &mut PresetCreationHelper,
-        app_config: &mut AppConfig
-    ) {
-        if let Err(_) = self.create_preset_value(pch) {
-            return;
-        }
-
-        let input = Self::format_input(&self.input.drain(..).collect::<String>());
-
-        self.messages.push(input);
-        self.cursor_idx = 0;
-
-        if self.messages.len() == self.prompts.len() {
-            pch.reset();
-
-            app_config.write_preset_to_file(&self.messages, WriteType::Create, CONFIG).unwrap();
+    /// Best fuzzy match of `pattern` against the name, tags and pane commands.
+    /// Name matches rank higher and are the only ones with indices to highlight.
+    pub fn fuzzy_match(&self, pattern: &str) -> Option<FuzzyMatch> {
+        let name_match = fuzzy::fuzzy_match(pattern, &self.name).map(|matched| FuzzyMatch {
+            score: matched.score * 2,
+            indices: matched.indices,
+        });
 
-            self.popup.activate_popup("Preset created successfuly :)", Color::Green);
-
-            self.handle_state_change(("", self.previous_state), None);
+        let other_match = self.tags
+            .iter()
+            .chain(self.args.iter())
+            .filter_map(|text| fuzzy::fuzzy_match(pattern, text))
+            .map(|matched| FuzzyMatch { score: matched.score, indices: vec![] })
+            .max_by_key(|matched| matched.score);
+
+        match (name_match, other_match) {
+            (Some(name_match), Some(other_match)) if other_match.score > name_match.score =>
+                Some(other_match),
+            (Some(name_match), _) => Some(name_match),
+            (None, other_match) => other_match,
         }
     }
 
-    pub fn handle_deleting_preset(&mut self, app_config: &mut AppConfig) {
-        let Some(item) = self.items.get_selected_item() else {
-            return;
-        };
-
-        if let Err(err) = app_config.delete_preset_by_name(&item.name) {
-            error!("{}", err);
-            return;
-        }
-        if let Err(err) = self.items.delete_selected_item() {
-            error!("{}", err);
-            return;
-        }
-        app_config
-            .write_preset_to_file(&self.messages, WriteType::Edit, CONFIG)
-            .expect("Error when writing to a file of a deleted preset.");
-
-        if self.items.items.is_empty() {
-            self.handle_state_change(("", self.previous_state), Some(&app_config));
-        }
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
     }
 
-    pub fn handle_editing_preset(&mut self, app_config: &mut AppConfig) {
-        let Some(item) = self.items.get_selected_item() else {
-            return;
-        };
-
-        if let Some(pr) = &self.current_preset {
-            let Some(pr) = app_config.get_mut_preset_by_name(&pr.name) else {
-                return;
-            };
-            let Some(mut pr_value) = item.preset_value else {
-                return;
-            };
-
-            let input = App::format_input(&self.input);
-
-            if let Err(err) = pr_value.update_value(&input) {
-                error!("{}", err);
-                return;
-            }
-
-            if let Err(err) = pr.change_field_value(pr_value) {
-                error!("{}", err);
-                return;
-            }
+    /// Args of the windows in the given tab.
+    pub fn get_tab_args(&self, tab: usize) -> Option<&[String]> {
+        let first = self.get_arg_index(tab, 0)?;
+        let windows = self.windows[tab] as usize;
 
-            self.current_preset = Some(pr.clone());
-        } else {
-            let Some(index) = self.items.get_selected_item_index() else {
-                return;
-            };
+        self.args.get(first..first + windows)
+    }
 
-            if let Err(err) = app_config.settings.change_name(index, &self.input) {
-                error!("{}", err);
-                return;
-            }
+    pub fn get_arg_index(&self, tab: usize, window: usize) -> Option<usize> {
+        let windows = *self.windows.get(tab)? as usize;
+        if window >= windows {
+            return None;
         }
 
-        app_config
-            .write_preset_to_file(&self.messages, WriteType::Edit, CONFIG)
-            .expect("Error when writing to a file of an edited preset.");
+        let args_before = self.windows[0..tab]
+            .iter()
+            .map(|w| *w as usize)
+            .sum::<usize>();
 
-        self.handle_state_change(("", self.previous_state), Some(&app_config));
+        Some(args_before + window)
     }
+}
 
-    pub fn cancel_preset_creation(&mut self, pch: &mut PresetCreationHelper) {
-        if self.popup.active {
-            self.popup.deactivate_popup();
-            return;
+impl Default for Preset {
+    fn default() -> Preset {
+        let preset_info = PresetInfo::default();
+        Preset {
+            name: String::from("Test Preset"),
+            tabs: 3,
+            windows: vec![2, 1, 1],
+            args: vec![
+                String::from("arg w1"),
+                String::from("arg w1"),
+                String::from("arg w2"),
+                String::from("arg w3")
+            ],
+            preset_info,
+            tags: Vec::new(),
+            layouts: Vec::new(),
         }
-        pch.reset();
-        self.handle_state_change(("", State::Start), None);
     }
+}
 
-    pub fn go_back(&mut self, app_config: &AppConfig) {
-        if self.popup.active {
-            self.popup.deactivate_popup();
-            return;
-        }
-
-        self.handle_state_change(("", self.previous_state), Some(app_config));
+impl AppConfig {
+    /// Directory for files the app writes besides the config, such as logs.
+    pub fn state_dir(config_path: &str) -> PathBuf {
+        let parent = Path::new(config_path).parent().unwrap_or(Path::new(""));
+        parent.join("state")
     }
 
-    pub fn edit_preset(&mut self, app_config: &mut AppConfig) {
-        let Some(item) = self.items.get_selected_item() else {
-            return;
-        };
-
-        if item.leading_state != State::RunConfig {
-            return;
-        }
-
-        let Some(preset) = app_config.get_mut_preset_by_name(&item.name) else {
-            return;
-        };
-
-        self.current_preset = Some(preset.clone());
-        self.handle_state_change(("", State::EditPreset), Some(&app_config));
+    pub fn get_settings(&self) -> &Settings {
+        &self.settings
     }
-    pub fn choose_item(&mut self, app_config: &AppConfig) {
-        if self.popup.active {
-            self.popup.deactivate_popup();
-        }
-        let Some(item) = self.items.get_selected_item() else {
-            return;
-        };
 
-        self.handle_state_change((item.name.as_str(), item.leading_state), Some(app_config));
+    pub fn get_mut_settings(&mut self) -> &mut Settings {
+        &mut self.settings
     }
 
-    pub fn handle_state_change(
-        &mut self,
-        (item_name, new_state): (&str, State),
-        app_config: Option<&AppConfig>
-    ) {
-        if new_state == self.state {
-            return;
-        }
-
-        self.previous_state = match new_state {
-            State::EditPreset => State::ChoosePreset,
-            State::ChangeFieldName => self.get_state(),
-            _ => State::Start,
-        };
-        self.state = new_state;
-
-        self.input.clear();
-        self.selected_input.clear();
-        self.cursor_idx = 0;
-        self.messages.clear();
-
-        match new_state {
-            State::CreatePreset => {
-                self.items.items.clear();
-                let new_prompts = self.state.create_prompts().unwrap();
+    pub fn get_presets(&self) -> &[Preset] {
+        &self.presets
+    }
 
-                for prompt in new_prompts {
-                    self.prompts.push(prompt);
-                }
-                self.input_mode = InputMode::Input;
-                self.items.list_state.select(Some(0));
-            }
-            State::ChoosePreset => {
-                self.items.items.clear();
-                let Some(config) = app_config else {
-                    return;
-                };
+    pub fn load(config_path: &str) -> Result<AppConfig, String> {
+        let cfg_file_string = fs
+            ::read_to_string(config_path)
+            .map_err(|err| format!("Cannot read the config file '{}': {}", config_path, err))?;
 
-                match &config.presets {
-                    presets if !presets.is_empty() => {
-                        for preset in presets {
-                            let item = Item::new(preset.name.to_string(), State::RunConfig, None);
-                            self.items.items.push(item);
-                        }
-                    }
-                    _ => {
-                        self.handle_state_change(("", State::Start), None);
-                        self.popup.activate_popup("No presets created.", Color::Red);
-                    }
-                }
-                self.items.list_state.select(Some(0));
-            }
-            State::EditPreset => {
-                self.input_mode = InputMode::Normal;
-                self.items.items.clear();
-                match app_config {
-                    Some(config) if !config.presets.is_empty() => {
-                        let mut new_items = self.current_preset.clone().unwrap().into_items();
-
-                        self.items.items.append(&mut new_items);
-                    }
-                    _ => {}
-                }
-            }
-            State::ChangeFieldName => {
-                self.input_mode = InputMode::Edit;
-                let index = item_name.find(":").unwrap();
-                let new_input = item_name[index + 1..].trim();
-                self.input = new_input.to_string();
-                self.cursor_idx = self.input.len();
-            }
-            State::RunConfig => {}
-            _ => {
-                self.items.items.clear();
-                self.prompts.clear();
-                self.current_preset = None;
-                self.input_mode = InputMode::Normal;
-                let new_items = self.state.create_items(app_config).unwrap();
-
-                for item in new_items {
-                    self.items.items.push(item);
-                }
-                self.items.list_state.select(Some(0));
-                if let Some(config) = app_config {
-                    self.debug_mode = config.settings.debug_mode;
-                }
-            }
-        }
+        serde_json
+            ::from_str(&cfg_file_string)
+            .map_err(|err| format!("Cannot parse the config file '{}': {}", config_path, err))
     }
-}
 
-impl AppConfig {
     pub fn get_preset_by_name(&self, name: &str) -> Option<&Preset> {
-        if let Some(found) = self.presets.iter().find(|preset| preset.name == name) {
-            Some(found)
-        } else {
-            None
-        }
+        self.presets.iter().find(|preset| preset.name == name)
     }
     pub fn get_mut_preset_by_name(&mut self, name: &str) -> Option<&mut Preset> {
-        if let Some(found) = self.presets.iter_mut().find(|preset| preset.name == name) {
-            Some(found)
-        } else {
-            None
-        }
+        self.presets.iter_mut().find(|preset| preset.name == name)
     }
 
     pub fn delete_preset_by_name(&mut self, name: &str) -> Result<(), String> {
@@ -930,15 +674,66 @@ impl AppConfig {
         Ok(())
     }
 
-    pub fn default() -> AppConfig {
-        let settings = Settings::default();
+    pub fn get_trash(&self) -> &[TrashedPreset] {
+        &self.trash
+    }
 
-        let app_config = AppConfig {
-            presets: vec![],
-            settings,
+    /// Moves the preset to the trash, `now` is stored as its deletion time.
+    pub fn trash_preset_by_name(&mut self, name: &str, now: SystemTime) -> Result<(), String> {
+        let Some(index) = self.presets.iter().position(|pr| pr.name == name) else {
+            return Err(String::from("Cannot delete preset: PRESET WITH GIVEN NAME WAS NOT FOUND."));
+        };
+
+        let preset = self.presets.remove(index);
+        let deleted_at = now
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        self.trash.push(TrashedPreset { preset, deleted_at });
+
+        Ok(())
+    }
+
+    /// Moves the trashed preset at `index` back to the presets and returns its name.
+    pub fn restore_preset(&mut self, index: usize) -> Result<String, String> {
+        let Some(trashed) = self.trash.get(index) else {
+            return Err(String::from("Cannot restore preset: INDEX OUT OF BOUNDS."));
         };
+        if self.get_preset_by_name(&trashed.preset.name).is_some() {
+            return Err(
+                format!(
+                    "Cannot restore preset: A PRESET NAMED '{}' ALREADY EXISTS.",
+                    trashed.preset.name
+                )
+            );
+        }
 
-        app_config
+        let trashed = self.trash.remove(index);
+        let name = trashed.preset.name.clone();
+        self.presets.push(trashed.preset);
+
+        Ok(name)
+    }
+
+    pub fn purge_preset(&mut self, index: usize) -> Result<(), String> {
+        if index >= self.trash.len() {
+            return Err(String::from("Cannot delete preset: INDEX OUT OF BOUNDS."));
+        }
+        self.trash.remove(index);
+
+        Ok(())
+    }
+
+    /// Removes presets trashed more than `Settings::trash_days` ago and returns how many.
+    pub fn purge_expired_trash(&mut self, now: SystemTime) -> usize {
+        let days = self.settings.trash_days as u64;
+        if days == 0 {
+            return 0;
+        }
+
+        let before = self.trash.len();
+        self.trash.retain(|trashed| trashed.days_since_deleted(now) < days);
+        before - self.trash.len()
     }
 
     pub fn add_presets(&mut self, presets: Vec<Preset>) {
@@ -949,33 +744,56 @@ impl AppConfig {
 
     pub fn write_preset_to_file(
         &mut self,
-        app_messages: &Vec<String>,
+        app_messages: &[String],
         write_type: WriteType,
         config_path: &str
-    ) -> Result<(), ()> {
+    ) -> Result<(), String> {
         if let WriteType::Create = write_type {
             let new_preset = Preset::from_input(app_messages);
             self.presets.push(new_preset);
         }
 
-        let config_file = File::create(config_path);
+        self.save(config_path)
+    }
 
-        if config_file.is_err() {
-            error!("Error while reading the config file.");
-            panic!();
+    /// Writes the config to `config_path`, after copying the previous file into
+    /// `Settings::backup_dir` when one is set.
+    pub fn save(&self, config_path: &str) -> Result<(), String> {
+        if let Some(backup_dir) = &self.settings.backup_dir {
+            AppConfig::backup(config_path, backup_dir)?;
         }
 
-        let mut writer = BufWriter::new(config_file.unwrap());
-        serde_json::to_writer(&mut writer, &self).unwrap();
-        writer.flush().unwrap();
+        let config_file = File::create(config_path).map_err(|err| {
+            error!("Error while opening the config file: {}", err);
+            format!("Cannot write the config file '{}': {}", config_path, err)
+        })?;
+
+        let mut writer = BufWriter::new(config_file);
+        serde_json::to_writer(&mut writer, &self).map_err(|err| err.to_string())?;
+        writer.flush().map_err(|err| err.to_string())?;
 
         Ok(())
     }
 
+    /// Copies the config at `config_path` into `backup_dir` as `<file name>.bak`,
+    /// replacing the previous backup. Nothing is copied before the first save.
+    fn backup(config_path: &str, backup_dir: &str) -> Result<(), String> {
+        let config = Path::new(config_path);
+        if !config.exists() {
+            return Ok(());
+        }
+        let mut name = config.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+
+        fs::copy(config, Path::new(backup_dir).join(name))
+            .map(|_| ())
+            .map_err(|err| format!("Cannot back up the config into '{}': {}", backup_dir, err))
+    }
+
     pub fn create_wt_command(&self, selected_name: &str) -> Result<(String, String), String> {
         let Some(preset) = self.get_preset_by_name(selected_name) else {
             error!("Cannot find the matching preset name while trying to run the config.");
-            panic!();
+            return Err(String::from("Cannot run preset: PRESET WITH GIVEN NAME WAS NOT FOUND."));
         };
 
         let wt_profile = &preset.preset_info.wt_profile;
@@ -1032,7 +850,29 @@ impl AppConfig {
             .for_each(|(i, x)| {
                 *x = i;
             });
-        for window in &preset.windows {
+        for (tab, window) in preset.windows.iter().enumerate() {
+            // Tabs arranged in the layout editor are built from their layout, the
+            // others keep the commands below.
+            let layout = preset.layout(tab);
+            if layout != PaneLayout::default_for(*window) {
+                let tab_args = arg_idx
+                    .drain(..*window as usize)
+                    .map(|index| args[index].clone())
+                    .collect::<Vec<String>>();
+                let mut command = format!("{} {}", wt_profile, tab_args[layout.panes()[0]]);
+                let mut next_pane = 1;
+                create_split_commands(
+                    &layout,
+                    0,
+                    &mut next_pane,
+                    &tab_args,
+                    &format!("{}; ", escape_char),
+                    &wt_profile,
+                    &mut command
+                );
+                windows.push(command);
+                continue;
+            }
             match window {
                 1 =>
                     windows.push(
@@ -1086,12 +926,16 @@ impl AppConfig {
         }
 
         for s in &mut windows[0..w_len - 1].iter_mut() {
-            s.push_str(&format!("`; nt"));
+            s.push_str("`; nt");
         }
 
         let windows = windows.into_iter().collect::<String>();
 
-        let arg = format!("wt.exe{}", windows);
+        let window = match self.settings.launcher {
+            Launcher::Wt => "",
+            Launcher::WtTabs => " -w 0",
+        };
+        let arg = format!("wt.exe{}{}", window, windows);
         log::warn!("{}", &arg);
 
         init_shell_name.push_str(".exe");
@@ -1099,134 +943,411 @@ impl AppConfig {
         Ok((init_shell_name, arg))
     }
 
+    pub fn create_launch_plan(&self, selected_name: &str) -> Result<LaunchPlan, String> {
+        let Some(preset) = self.get_preset_by_name(selected_name) else {
+            return Err(String::from("Cannot run preset: PRESET WITH GIVEN NAME WAS NOT FOUND."));
+        };
+
+        let problems = preset.validate();
+        if !problems.is_empty() {
+            return Err(format!("Cannot run preset: {}", problems.join(" ")));
+        }
+
+        let (program, arg) = self.create_wt_command(selected_name)?;
+
+        Ok(LaunchPlan {
+            backend: self.settings.launcher.as_string(),
+            program,
+            args: vec![arg],
+        })
+    }
+
     pub fn new(presets: Vec<Preset>, settings: Settings) -> AppConfig {
         AppConfig {
             presets,
             settings,
+            trash: Vec::new(),
         }
     }
 }
 
 impl Settings {
-    pub fn change_name(&mut self, index: usize, new_name: &str) -> Result<(), String> {
-        match index {
-            0 => {
-                match new_name {
-                    "true" => {
-                        self.debug_mode = true;
-                    }
-                    "false" => {
-                        self.debug_mode = false;
-                    }
-                    _ => {
-                        return Err(
-                            String::from(
-                                "Cannot change the setting name: EXPECTED 'true' or 'false'."
-                            )
-                        );
-                    }
+    pub fn get_launcher(&self) -> Launcher {
+        self.launcher
+    }
+
+    /// The editable settings, in the order the Settings screen lists them.
+    pub fn form_fields(&self) -> Vec<FormField> {
+        let init_shells = ShellType::init_names();
+
+        vec![
+            FormField::new(FieldTarget::DebugMode, "Debug mode", FieldValue::Toggle(self.debug_mode)),
+            FormField::new(
+                FieldTarget::TrashDays,
+                "Trash retention days",
+                FieldValue::integer(self.trash_days as i64, 0, 3650)
+            ),
+            FormField::new(
+                FieldTarget::DefaultWtProfile,
+                "Default wt profile",
+                FieldValue::text(&self.preset_defaults.wt_profile, false)
+            ),
+            FormField::new(
+                FieldTarget::DefaultInitShell,
+                "Default init shell",
+                FieldValue::choice(init_shells, &self.preset_defaults.init_shell.as_string())
+            ),
+            FormField::new(
+                FieldTarget::DefaultTargetShell,
+                "Default target shell",
+                FieldValue::choice(ShellType::names(), &self.preset_defaults.target_shell.as_string())
+            ),
+            FormField::new(
+                FieldTarget::CloseAfterLaunch,
+                "Close after launch",
+                FieldValue::Toggle(self.close_after_launch)
+            ),
+            FormField::new(
+                FieldTarget::ConfirmBeforeLaunch,
+                "Confirm before launch",
+                FieldValue::Toggle(self.confirm_before_launch)
+            ),
+            FormField::new(
+                FieldTarget::Launcher,
+                "Launcher",
+                FieldValue::choice(Launcher::names(), &self.launcher.as_string())
+            ),
+            FormField::new(
+                FieldTarget::Theme,
+                "Theme",
+                FieldValue::choice(self.theme.names(), &self.theme.name)
+            ),
+            FormField::new(
+                FieldTarget::LogLevel,
+                "Log level",
+                FieldValue::choice(LogLevel::names(), &self.log.level.as_string())
+            ),
+            FormField::new(
+                FieldTarget::LogFile,
+                "Log file",
+                FieldValue::Path {
+                    path: self.log.path.clone().unwrap_or_default(),
+                    must_exist: false,
+                }
+            ),
+            FormField::new(
+                FieldTarget::BackupDir,
+                "Backup folder",
+                FieldValue::Path {
+                    path: self.backup_dir.clone().unwrap_or_default(),
+                    must_exist: true,
                 }
+            )
+        ]
+    }
+
+    /// Stores the value of a field from `form_fields`.
+    pub fn apply_field(&mut self, field: &FormField) -> Result<(), String> {
+        let value = field.get_value();
+
+        match field.get_target() {
+            FieldTarget::DebugMode => {
+                self.debug_mode = value.as_toggle().unwrap_or(self.debug_mode);
+            }
+            FieldTarget::TrashDays => {
+                self.trash_days = value.as_integer().unwrap_or(0) as u32;
+            }
+            FieldTarget::DefaultWtProfile => {
+                self.preset_defaults.wt_profile = value.to_string();
+            }
+            FieldTarget::DefaultInitShell => {
+                self.preset_defaults.init_shell = value.to_string().parse::<ShellType>()?;
+            }
+            FieldTarget::DefaultTargetShell => {
+                self.preset_defaults.target_shell = value.to_string().parse::<ShellType>()?;
+            }
+            FieldTarget::CloseAfterLaunch => {
+                self.close_after_launch = value.as_toggle().unwrap_or(self.close_after_launch);
+            }
+            FieldTarget::ConfirmBeforeLaunch => {
+                self.confirm_before_launch = value.as_toggle().unwrap_or(self.confirm_before_launch);
+            }
+            FieldTarget::Launcher => {
+                self.launcher = value.to_string().parse::<Launcher>()?;
+            }
+            FieldTarget::Theme => {
+                self.theme.name = value.to_string();
+            }
+            FieldTarget::LogLevel => {
+                self.log.level = value.to_string().parse::<LogLevel>()?;
+            }
+            FieldTarget::LogFile => {
+                self.log.path = Some(value.to_string()).filter(|path| !path.is_empty());
+            }
+            FieldTarget::BackupDir => {
+                self.backup_dir = Some(value.to_string()).filter(|path| !path.is_empty());
             }
             _ => {
-                return Err(String::from("Cannot change the setting name: INDEX OUT OF BOUNDS."));
+                return Err(String::from("Cannot change the setting: NOT A SETTINGS FIELD."));
             }
         }
         Ok(())
     }
 
-    pub fn default() -> Settings {
+    /// Validates `input` with the field for `target` and stores it.
+    pub fn set_field(&mut self, target: FieldTarget, input: &str) -> Result<(), String> {
+        let Some(mut field) = self
+            .form_fields()
+            .into_iter()
+            .find(|field| field.get_target() == target) else {
+            return Err(String::from("Cannot change the setting: NOT A SETTINGS FIELD."));
+        };
+
+        field.submit(input)?;
+        self.apply_field(&field)
+    }
+
+    pub(super) fn default_trash_days() -> u32 {
+        30
+    }
+
+    pub(super) fn default_close_after_launch() -> bool {
+        true
+    }
+}
+
+/// Appends the `split-pane` commands that turn the focused pane, Windows
+/// Terminal pane `pane_id` already running the first window of `layout`, into
+/// `layout`. Every split focuses the new pane, so `focus-pane` goes back before
+/// the first half is split further.
+fn create_split_commands(
+    layout: &PaneLayout,
+    pane_id: usize,
+    next_pane: &mut usize,
+    args: &[String],
+    separator: &str,
+    wt_profile: &str,
+    command: &mut String
+) {
+    let PaneLayout::Split { direction, ratio, first, second } = layout else {
+        return;
+    };
+    let new_pane = *next_pane;
+    *next_pane += 1;
+
+    let direction = match direction {
+        SplitDirection::Vertical => "-V",
+        SplitDirection::Horizontal => "-H",
+    };
+    command.push_str(
+        &format!(
+            "{}sp {} -s .{:02}{} {}",
+            separator,
+            direction,
+            100 - ratio,
+            wt_profile,
+            args[second.panes()[0]]
+        )
+    );
+    create_split_commands(second, new_pane, next_pane, args, separator, wt_profile, command);
+
+    if let PaneLayout::Split { .. } = **first {
+        command.push_str(&format!("{}fp -t {}", separator, pane_id));
+        create_split_commands(first, pane_id, next_pane, args, separator, wt_profile, command);
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
         Settings {
             debug_mode: false,
+            log: LogSettings::default(),
+            keymap: KeymapSettings::default(),
+            theme: ThemeSettings::default(),
+            trash_days: Settings::default_trash_days(),
+            preset_defaults: PresetInfo::default(),
+            close_after_launch: Settings::default_close_after_launch(),
+            confirm_before_launch: false,
+            launcher: Launcher::default(),
+            backup_dir: None,
         }
     }
 }
 
-impl PresetCreationHelper {
-    pub fn new() -> Self {
-        PresetCreationHelper {
-            windows: VecDeque::new(),
-            max_windows: 0,
+impl TrashedPreset {
+    pub fn get_preset(&self) -> &Preset {
+        &self.preset
+    }
+
+    pub fn days_since_deleted(&self, now: SystemTime) -> u64 {
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        now.saturating_sub(self.deleted_at) / (24 * 60 * 60)
+    }
+}
+
+impl Default for ThemeSettings {
+    fn default() -> ThemeSettings {
+        ThemeSettings {
+            name: String::from("dark"),
+            custom: BTreeMap::new(),
         }
     }
+}
 
-    pub fn reset(&mut self) {
-        self.windows.clear();
-        self.max_windows = 0;
+impl ThemeSettings {
+    /// The built-in themes followed by the custom ones. A configured name that is
+    /// neither stays in the list, so it is not replaced by opening the field.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = BUILT_IN_THEMES.iter()
+            .map(|name| name.to_string())
+            .chain(
+                self.custom
+                    .keys()
+                    .filter(|name| !BUILT_IN_THEMES.contains(&name.as_str()))
+                    .cloned()
+            )
+            .collect::<Vec<String>>();
+        if !names.contains(&self.name) {
+            names.push(self.name.clone());
+        }
+        names
     }
 }
 
-impl PresetValue {
-    pub fn update_value(&mut self, new_val: &str) -> Result<(), String> {
+impl Default for LogSettings {
+    fn default() -> LogSettings {
+        LogSettings {
+            path: None,
+            level: LogLevel::Info,
+            max_size_kb: 1024,
+            max_files: 3,
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "off" => Ok(LogLevel::Off),
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err(String::from("Incorrect log level name.")),
+        }
+    }
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 6] = [
+        LogLevel::Off,
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn as_string(&self) -> String {
         match self {
-            PresetValue::Name(name) => {
-                *name = new_val.to_string();
-            }
+            LogLevel::Off => "off".to_string(),
+            LogLevel::Error => "error".to_string(),
+            LogLevel::Warn => "warn".to_string(),
+            LogLevel::Info => "info".to_string(),
+            LogLevel::Debug => "debug".to_string(),
+            LogLevel::Trace => "trace".to_string(),
+        }
+    }
 
-            PresetValue::Tabs(tabs) => {
-                let Ok(new_tabs) = new_val.parse::<u8>() else {
-                    return Err(
-                        String::from(
-                            "PresetValue, update_value error parsing tabs: CANNOT PARSE GIVEN STRING."
-                        )
-                    );
-                };
-                if new_tabs > 10 {
-                    return Err(
-                        String::from(
-                            "PresetValue, update_value error creating tabs: INVALID AMOUNT OF TABS"
-                        )
-                    );
-                }
-                *tabs = new_tabs;
-            }
-            PresetValue::Windows(_, windows) => {
-                let Ok(new_windows) = new_val.parse::<u8>() else {
-                    return Err(
-                        String::from(
-                            "PresetValue update_value error parsing windows: CANNOT PARSE GIVEN STRING."
-                        )
-                    );
-                };
-                if new_windows > 4 {
-                    return Err(
-                        String::from(
-                            "PresetValue, update_value error creating windows: INVALID AMOUNT OF WINDOWS"
-                        )
-                    );
-                }
-                *windows = new_windows;
-            }
-            PresetValue::Args(_, arg) => {
-                *arg = new_val.to_string();
-            }
-            PresetValue::PresetInfo(preset_info_value) =>
-                match preset_info_value {
-                    PresetInfoValue::WtProfile(name) => {
-                        *name = new_val.to_string();
-                    }
-                    PresetInfoValue::InitShell(init_shell) => {
-                        *init_shell = match new_val {
-                            "powershell" | "cmd" | "pwsh" => ShellType::from_str(new_val)?,
-                            _ => {
-                                return Err(
-                                    String::from(
-                                        "PresetValue update_value error parsing PresetInfo: INIT SHELL MUST BE 'powershell' OR 'cmd' OR 'pwsh'."
-                                    )
-                                );
-                            }
-                        };
-                    }
-                    PresetInfoValue::TargetShell(target_shell) => {
-                        *target_shell = ShellType::from_str(new_val)?;
-                    }
-                }
+    pub fn names() -> Vec<String> {
+        LogLevel::ALL.iter().map(LogLevel::as_string).collect()
+    }
+
+    pub fn to_level_filter(self) -> log::LevelFilter {
+        match self {
+            LogLevel::Off => log::LevelFilter::Off,
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace,
         }
+    }
+}
+
+impl Launcher {
+    pub const ALL: [Launcher; 2] = [Launcher::Wt, Launcher::WtTabs];
+
+    pub fn as_string(&self) -> String {
+        match self {
+            Launcher::Wt => "wt".to_string(),
+            Launcher::WtTabs => "wt-tabs".to_string(),
+        }
+    }
+
+    pub fn names() -> Vec<String> {
+        Launcher::ALL.iter().map(Launcher::as_string).collect()
+    }
+}
+
+impl FromStr for Launcher {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "wt" => Ok(Launcher::Wt),
+            "wt-tabs" => Ok(Launcher::WtTabs),
+            _ => Err(format!("Unknown launcher '{}', expected wt or wt-tabs.", name)),
+        }
+    }
+}
+
+impl LaunchPlan {
+    /// The launcher that produced the plan, e.g. `wt` or `wt-tabs`.
+    pub fn get_backend(&self) -> &str {
+        &self.backend
+    }
+
+    pub fn get_program(&self) -> &str {
+        &self.program
+    }
+
+    pub fn get_args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Starts the program without waiting for it; the child is reaped on a background thread.
+    pub fn run(&self) -> Result<(), String> {
+        let mut process = std::process::Command
+            ::new(&self.program)
+            .args(&self.args)
+            .spawn()
+            .map_err(|err| format!("Failed to launch the target process '{}': {}", self.program, err))?;
+
+        // Reaped in the background so neither the TUI nor the CLI blocks on it.
+        std::thread::spawn(move || process.wait());
 
         Ok(())
     }
 }
 
 impl ShellType {
+    pub const ALL: [ShellType; 6] = [
+        ShellType::WindowsPowershell,
+        ShellType::Powershell,
+        ShellType::Cmd,
+        ShellType::Bash,
+        ShellType::Zsh,
+        ShellType::Fish,
+    ];
+    pub const INIT: [ShellType; 3] = [ShellType::WindowsPowershell, ShellType::Powershell, ShellType::Cmd];
+
     pub fn as_string(&self) -> String {
         match self {
             ShellType::WindowsPowershell => "powershell".to_string(),
@@ -1238,7 +1359,20 @@ impl ShellType {
         }
     }
 
-    pub fn from_str(name: &str) -> Result<Self, String> {
+    pub fn names() -> Vec<String> {
+        ShellType::ALL.iter().map(ShellType::as_string).collect()
+    }
+
+    /// The shells that can run wt.exe, the only ones allowed as the init shell.
+    pub fn init_names() -> Vec<String> {
+        ShellType::INIT.iter().map(ShellType::as_string).collect()
+    }
+}
+
+impl FromStr for ShellType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
         match name {
             "powershell" => Ok(ShellType::WindowsPowershell),
             "pwsh" => Ok(ShellType::Powershell),
@@ -1246,9 +1380,7 @@ impl ShellType {
             "bash" => Ok(ShellType::Bash),
             "zsh" => Ok(ShellType::Zsh),
             "fish" => Ok(ShellType::Fish),
-            _ => {
-                return Err("Incorrect shell name.".to_string());
-            }
+            _ => Err("Incorrect shell name.".to_string()),
         }
     }
 }
@@ -1266,15 +1398,17 @@ impl Display for ShellType {
     }
 }
 
-impl PresetInfo {
-    pub fn default() -> PresetInfo {
+impl Default for PresetInfo {
+    fn default() -> PresetInfo {
         PresetInfo {
             wt_profile: String::new(),
             init_shell: ShellType::WindowsPowershell,
             target_shell: ShellType::WindowsPowershell,
         }
     }
+}
 
+impl PresetInfo {
     pub fn new(wt_profile: String, init_shell: ShellType, target_shell: ShellType) -> PresetInfo {
         PresetInfo {
             wt_profile,
@@ -1282,31 +1416,16 @@ impl PresetInfo {
             target_shell,
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn presetvalue_update_success() {
-        let mut pv = PresetValue::Args(0, "Not".to_string());
-        let target = PresetValue::Args(0, "Changed".to_string());
-        pv.update_value("Changed").unwrap();
 
-        assert_eq!(pv, target);
+    pub fn get_wt_profile(&self) -> &str {
+        &self.wt_profile
     }
 
-    #[test]
-    #[should_panic]
-    fn presetvalue_update_fail_windows() {
-        let mut pv = PresetValue::Windows(0, 4);
-        pv.update_value("5").unwrap();
+    pub fn get_init_shell(&self) -> &ShellType {
+        &self.init_shell
     }
 
-    #[test]
-    #[should_panic]
-    fn presetvalue_update_fail_tabs() {
-        let mut pv = PresetValue::Tabs(10);
-        pv.update_value("11").unwrap();
+    pub fn get_target_shell(&self) -> &ShellType {
+        &self.target_shell
     }
 }
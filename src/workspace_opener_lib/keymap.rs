@@ -0,0 +1,509 @@
+use super::model::{ Action, KeymapPreset, KeymapSettings };
+use crossterm::event::{ KeyCode, KeyEvent, KeyModifiers };
+use std::{ fmt::Display, str::FromStr };
+
+/// Lists and menus react to `List` actions, the input box to `Text` ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyContext {
+    List,
+    Text,
+}
+
+/// A single key with its modifiers. Shift is folded into the character for
+/// printable keys, so `shift+g` and `G` are the same press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+/// Resolves key presses to actions. Bindings may be sequences such as `g g`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyPress>)>,
+}
+
+impl Action {
+    pub const ALL: [Action; 43] = [
+        Action::Quit,
+        Action::Up,
+        Action::Down,
+        Action::First,
+        Action::Last,
+        Action::Launch,
+        Action::Back,
+        Action::Edit,
+        Action::Delete,
+        Action::Search,
+        Action::Help,
+        Action::InsertBefore,
+        Action::InsertAfter,
+        Action::Duplicate,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Layout,
+        Action::Toggle,
+        Action::SplitVertical,
+        Action::SplitHorizontal,
+        Action::Grow,
+        Action::Shrink,
+        Action::Submit,
+        Action::Cancel,
+        Action::PreviousStep,
+        Action::Left,
+        Action::Right,
+        Action::WordLeft,
+        Action::WordRight,
+        Action::SelectLeft,
+        Action::SelectRight,
+        Action::Home,
+        Action::End,
+        Action::SelectAll,
+        Action::Backspace,
+        Action::DeleteForward,
+        Action::DeleteWordLeft,
+        Action::DeleteWordRight,
+        Action::Copy,
+        Action::Cut,
+        Action::Paste,
+        Action::Undo,
+        Action::Redo,
+    ];
+
+    pub fn context(&self) -> KeyContext {
+        match self {
+            | Action::Quit
+            | Action::Up
+            | Action::Down
+            | Action::First
+            | Action::Last
+            | Action::Launch
+            | Action::Back
+            | Action::Edit
+            | Action::Delete
+            | Action::Search
+            | Action::Help
+            | Action::InsertBefore
+            | Action::InsertAfter
+            | Action::Duplicate
+            | Action::MoveLeft
+            | Action::MoveRight
+            | Action::Layout
+            | Action::Toggle
+            | Action::SplitVertical
+            | Action::SplitHorizontal
+            | Action::Grow
+            | Action::Shrink => KeyContext::List,
+            _ => KeyContext::Text,
+        }
+    }
+
+    fn default_keys(&self, preset: KeymapPreset) -> &'static [&'static str] {
+        match (self, preset) {
+            (Action::Up, KeymapPreset::Vim) => &["up", "k"],
+            (Action::Down, KeymapPreset::Vim) => &["down", "j"],
+            (Action::First, KeymapPreset::Vim) => &["home", "g g"],
+            (Action::Last, KeymapPreset::Vim) => &["end", "G"],
+            (Action::Quit, _) => &["q"],
+            (Action::Up, _) => &["up"],
+            (Action::Down, _) => &["down"],
+            (Action::First, _) => &["home"],
+            (Action::Last, _) => &["end"],
+            (Action::Launch, _) => &["enter"],
+            (Action::Back, _) => &["esc"],
+            (Action::Edit, _) => &["e"],
+            (Action::Delete, _) => &["delete"],
+            (Action::Search, _) => &["/"],
+            (Action::Help, _) => &["?", "f1"],
+            (Action::InsertBefore, _) => &["I"],
+            (Action::InsertAfter, _) => &["i"],
+            (Action::Duplicate, _) => &["d"],
+            (Action::MoveLeft, _) => &["<"],
+            (Action::MoveRight, _) => &[">"],
+            (Action::Layout, _) => &["l"],
+            (Action::Toggle, _) => &["space"],
+            (Action::SplitVertical, _) => &["v"],
+            (Action::SplitHorizontal, _) => &["h"],
+            (Action::Grow, _) => &["+"],
+            (Action::Shrink, _) => &["-"],
+            (Action::Submit, _) => &["enter"],
+            (Action::Cancel, _) => &["esc"],
+            (Action::PreviousStep, _) => &["backtab"],
+            (Action::Left, _) => &["left"],
+            (Action::Right, _) => &["right"],
+            (Action::WordLeft, _) => &["ctrl+left"],
+            (Action::WordRight, _) => &["ctrl+right"],
+            (Action::SelectLeft, _) => &["shift+left"],
+            (Action::SelectRight, _) => &["shift+right"],
+            (Action::Home, _) => &["home"],
+            (Action::End, _) => &["end"],
+            (Action::SelectAll, _) => &["ctrl+a"],
+            (Action::Backspace, _) => &["backspace"],
+            (Action::DeleteForward, _) => &["delete"],
+            (Action::DeleteWordLeft, _) => &["ctrl+backspace", "ctrl+w"],
+            (Action::DeleteWordRight, _) => &["ctrl+delete"],
+            (Action::Copy, _) => &["ctrl+c"],
+            (Action::Cut, _) => &["ctrl+x"],
+            (Action::Paste, _) => &["ctrl+v"],
+            (Action::Undo, _) => &["ctrl+z"],
+            (Action::Redo, _) => &["ctrl+y"],
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Action::Quit => "quit",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Launch => "launch",
+            Action::Back => "back",
+            Action::Edit => "edit",
+            Action::Delete => "delete",
+            Action::Search => "search",
+            Action::Help => "help",
+            Action::InsertBefore => "insert_before",
+            Action::InsertAfter => "insert_after",
+            Action::Duplicate => "duplicate",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Layout => "layout",
+            Action::Toggle => "toggle",
+            Action::SplitVertical => "split_vertical",
+            Action::SplitHorizontal => "split_horizontal",
+            Action::Grow => "grow",
+            Action::Shrink => "shrink",
+            Action::Submit => "submit",
+            Action::Cancel => "cancel",
+            Action::PreviousStep => "previous_step",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::WordLeft => "word_left",
+            Action::WordRight => "word_right",
+            Action::SelectLeft => "select_left",
+            Action::SelectRight => "select_right",
+            Action::Home => "home",
+            Action::End => "end",
+            Action::SelectAll => "select_all",
+            Action::Backspace => "backspace",
+            Action::DeleteForward => "delete_forward",
+            Action::DeleteWordLeft => "delete_word_left",
+            Action::DeleteWordRight => "delete_word_right",
+            Action::Copy => "copy",
+            Action::Cut => "cut",
+            Action::Paste => "paste",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl KeyPress {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyPress {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+
+        let code = match code {
+            KeyCode::Char(ch) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(ch.to_uppercase().next().unwrap_or(ch))
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+
+        KeyPress { code, modifiers }
+    }
+
+    /// The character typed by this press, if it types one.
+    pub fn get_char(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(ch) if self.modifiers.is_empty() => Some(ch),
+            _ => None,
+        }
+    }
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(event: KeyEvent) -> KeyPress {
+        KeyPress::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for KeyPress {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let invalid = || format!("Cannot parse key '{}'.", text);
+
+        // `+` on its own or at the end (`ctrl++`) is the plus key.
+        let (modifier_part, key) = match text.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None if text == "+" => ("", "+"),
+            None =>
+                match text.rsplit_once('+') {
+                    Some((modifiers, key)) => (modifiers, key),
+                    None => ("", text),
+                }
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifier_part.split('+').filter(|part| !part.is_empty()) {
+            modifiers |= match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => {
+                    return Err(invalid());
+                }
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower if lower.len() > 1 && lower.starts_with('f') => {
+                let number = lower[1..].parse::<u8>().map_err(|_| invalid())?;
+                KeyCode::F(number)
+            }
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => KeyCode::Char(ch),
+                    _ => {
+                        return Err(invalid());
+                    }
+                }
+            }
+        };
+
+        Ok(KeyPress::new(code, modifiers))
+    }
+}
+
+impl Display for KeyPress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "CTRL+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "ALT+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "SHIFT+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "SPACE"),
+            KeyCode::Char(ch) => write!(f, "{}", ch),
+            KeyCode::Enter => write!(f, "ENTER"),
+            KeyCode::Esc => write!(f, "ESC"),
+            KeyCode::Backspace => write!(f, "BACKSPACE"),
+            KeyCode::Delete => write!(f, "DEL"),
+            KeyCode::Insert => write!(f, "INS"),
+            KeyCode::Tab => write!(f, "TAB"),
+            KeyCode::BackTab => write!(f, "SHIFT+TAB"),
+            KeyCode::Up => write!(f, "UP"),
+            KeyCode::Down => write!(f, "DOWN"),
+            KeyCode::Left => write!(f, "LEFT"),
+            KeyCode::Right => write!(f, "RIGHT"),
+            KeyCode::Home => write!(f, "HOME"),
+            KeyCode::End => write!(f, "END"),
+            KeyCode::PageUp => write!(f, "PGUP"),
+            KeyCode::PageDown => write!(f, "PGDN"),
+            KeyCode::F(number) => write!(f, "F{}", number),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+fn parse_sequence(text: &str) -> Result<Vec<KeyPress>, String> {
+    let sequence = text
+        .split_whitespace()
+        .map(KeyPress::from_str)
+        .collect::<Result<Vec<KeyPress>, String>>()?;
+
+    match sequence.is_empty() {
+        true => Err(String::from("Cannot parse an empty key.")),
+        false => Ok(sequence),
+    }
+}
+
+fn sequence_to_string(sequence: &[KeyPress]) -> String {
+    sequence
+        .iter()
+        .map(|key| key.to_string())
+        .collect::<String>()
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::new(&KeymapSettings::default()).expect("The default keymap has conflicts.")
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap of `settings.preset` and replaces the keys of every action
+    /// listed in `settings.bindings`. Fails on keys that cannot be parsed or conflict.
+    pub fn new(settings: &KeymapSettings) -> Result<Keymap, String> {
+        let mut bindings = vec![];
+        let mut errors = vec![];
+
+        for action in Action::ALL {
+            let keys = match settings.bindings.get(&action) {
+                Some(keys) => keys.clone(),
+                None =>
+                    action
+                        .default_keys(settings.preset)
+                        .iter()
+                        .map(|key| key.to_string())
+                        .collect(),
+            };
+
+            for key in keys {
+                match parse_sequence(&key) {
+                    Ok(sequence) => bindings.push((action, sequence)),
+                    Err(err) => errors.push(format!("{} ({})", err, action)),
+                }
+            }
+        }
+
+        let keymap = Keymap { bindings };
+        errors.extend(keymap.conflicts());
+
+        match errors.is_empty() {
+            true => Ok(keymap),
+            false => Err(format!("Invalid keybindings: {}", errors.join(" "))),
+        }
+    }
+
+    /// Keys bound twice in one context, sequences that shadow longer ones and
+    /// text actions bound to keys that type characters.
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = vec![];
+
+        for (index, (action, sequence)) in self.bindings.iter().enumerate() {
+            if action.context() == KeyContext::Text && sequence[0].get_char().is_some() {
+                conflicts.push(
+                    format!(
+                        "'{}' types text and cannot be used for {}.",
+                        sequence_to_string(sequence),
+                        action
+                    )
+                );
+            }
+
+            for (other_action, other) in self.bindings.iter().skip(index + 1) {
+                if action.context() != other_action.context() || action == other_action {
+                    continue;
+                }
+
+                let shortest = sequence.len().min(other.len());
+                if sequence[..shortest] != other[..shortest] {
+                    continue;
+                }
+
+                match sequence.len() == other.len() {
+                    true =>
+                        conflicts.push(
+                            format!(
+                                "'{}' is bound to both {} and {}.",
+                                sequence_to_string(sequence),
+                                action,
+                                other_action
+                            )
+                        ),
+                    false =>
+                        conflicts.push(
+                            format!(
+                                "'{}' ({}) and '{}' ({}) start with the same keys.",
+                                sequence_to_string(sequence),
+                                action,
+                                sequence_to_string(other),
+                                other_action
+                            )
+                        ),
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Adds `key` to the `pending` keys of a sequence and returns the action once
+    /// a whole binding was typed. Keys that cannot continue any binding are dropped.
+    pub fn resolve(
+        &self,
+        context: KeyContext,
+        pending: &mut Vec<KeyPress>,
+        key: KeyPress
+    ) -> Option<Action> {
+        pending.push(key);
+
+        loop {
+            let mut candidates = self.bindings
+                .iter()
+                .filter(|(action, sequence)| {
+                    action.context() == context && sequence.starts_with(pending)
+                })
+                .peekable();
+
+            if let Some((action, _)) = candidates.clone().find(|(_, sequence)| sequence == pending) {
+                pending.clear();
+                return Some(*action);
+            }
+            if candidates.peek().is_some() {
+                return None;
+            }
+            if pending.len() > 1 {
+                pending.drain(..pending.len() - 1);
+                continue;
+            }
+
+            pending.clear();
+            return None;
+        }
+    }
+
+    /// The first key bound to `action`, as shown in the controls line.
+    pub fn label(&self, action: Action) -> Option<String> {
+        self.labels(action).into_iter().next()
+    }
+
+    /// Every key bound to `action`. With `typing` set, keys that would type text
+    /// into the input box are left out.
+    pub fn labels_for(&self, action: Action, typing: bool) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(bound, sequence)| {
+                *bound == action && !(typing && sequence[0].get_char().is_some())
+            })
+            .map(|(_, sequence)| sequence_to_string(sequence))
+            .collect()
+    }
+
+    pub fn labels(&self, action: Action) -> Vec<String> {
+        self.labels_for(action, false)
+    }
+}
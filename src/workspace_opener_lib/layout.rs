@@ -0,0 +1,320 @@
+use serde::{ Deserialize, Serialize };
+
+/// Windows Terminal naming: a `Vertical` split puts panes side by side, a
+/// `Horizontal` one stacks them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    Vertical,
+    Horizontal,
+}
+
+/// How the windows (panes) of one tab are arranged. `Pane` holds the index of
+/// the window inside its tab, `ratio` is the share of the first child in percent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PaneLayout {
+    Pane(usize),
+    Split {
+        direction: SplitDirection,
+        ratio: u16,
+        first: Box<PaneLayout>,
+        second: Box<PaneLayout>,
+    },
+}
+
+impl PaneLayout {
+    /// The arrangement `create_wt_command` produces for the given amount of windows.
+    pub fn default_for(windows: u8) -> PaneLayout {
+        match windows {
+            2 => PaneLayout::split(SplitDirection::Vertical, 50, PaneLayout::Pane(0), PaneLayout::Pane(1)),
+            3 =>
+                PaneLayout::split(
+                    SplitDirection::Vertical,
+                    34,
+                    PaneLayout::Pane(0),
+                    PaneLayout::split(SplitDirection::Vertical, 50, PaneLayout::Pane(1), PaneLayout::Pane(2))
+                ),
+            4 =>
+                PaneLayout::split(
+                    SplitDirection::Vertical,
+                    50,
+                    PaneLayout::split(SplitDirection::Horizontal, 50, PaneLayout::Pane(0), PaneLayout::Pane(3)),
+                    PaneLayout::split(SplitDirection::Horizontal, 50, PaneLayout::Pane(1), PaneLayout::Pane(2))
+                ),
+            _ => PaneLayout::Pane(0),
+        }
+    }
+
+    pub fn split(
+        direction: SplitDirection,
+        ratio: u16,
+        first: PaneLayout,
+        second: PaneLayout
+    ) -> PaneLayout {
+        PaneLayout::Split {
+            direction,
+            ratio,
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    /// Window indices from the top left pane to the bottom right one.
+    pub fn panes(&self) -> Vec<usize> {
+        match self {
+            PaneLayout::Pane(index) => vec![*index],
+            PaneLayout::Split { first, second, .. } => {
+                let mut panes = first.panes();
+                panes.extend(second.panes());
+                panes
+            }
+        }
+    }
+}
+
+/// Direction the focus moves in the layout editor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FocusDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Position and size of a pane on a 1000x1000 grid: `(x, y, width, height)`.
+type PaneArea = (u32, u32, u32, u32);
+
+const GRID: u32 = 1000;
+const MIN_RATIO: u16 = 10;
+const MAX_RATIO: u16 = 90;
+const MAX_PANES: usize = 4;
+
+impl PaneLayout {
+    /// Whether every window from 0 to `windows - 1` has exactly one pane.
+    pub fn fits(&self, windows: u8) -> bool {
+        let mut panes = self.panes();
+        panes.sort_unstable();
+        panes == (0..windows as usize).collect::<Vec<usize>>()
+    }
+
+    /// Whether every split gives its first child between 10 and 90 percent,
+    /// as the layout editor does. Hand edited configs may hold any number.
+    pub fn ratios_in_range(&self) -> bool {
+        match self {
+            PaneLayout::Pane(_) => true,
+            PaneLayout::Split { ratio, first, second, .. } =>
+                (MIN_RATIO..=MAX_RATIO).contains(ratio) &&
+                    first.ratios_in_range() &&
+                    second.ratios_in_range(),
+        }
+    }
+
+    /// Swaps the windows shown in two panes, used when windows are reordered.
+    pub fn swap_panes(&mut self, a: usize, b: usize) {
+        match self {
+            PaneLayout::Pane(index) if *index == a => {
+                *index = b;
+            }
+            PaneLayout::Pane(index) if *index == b => {
+                *index = a;
+            }
+            PaneLayout::Pane(_) => {}
+            PaneLayout::Split { first, second, .. } => {
+                first.swap_panes(a, b);
+                second.swap_panes(a, b);
+            }
+        }
+    }
+
+    fn contains(&self, pane: usize) -> bool {
+        self.panes().contains(&pane)
+    }
+
+    /// Splits `pane` in half, the new pane shows window `new_pane` and goes to
+    /// the right (`Vertical`) or below (`Horizontal`).
+    fn split_pane(&mut self, pane: usize, direction: SplitDirection, new_pane: usize) {
+        match self {
+            PaneLayout::Pane(index) if *index == pane => {
+                *self = PaneLayout::split(direction, 50, PaneLayout::Pane(pane), PaneLayout::Pane(new_pane));
+            }
+            PaneLayout::Pane(_) => {}
+            PaneLayout::Split { first, second, .. } => {
+                first.split_pane(pane, direction, new_pane);
+                second.split_pane(pane, direction, new_pane);
+            }
+        }
+    }
+
+    /// Gives the space of `pane` to its sibling and renumbers the panes after it.
+    fn remove_pane(&mut self, pane: usize) {
+        self.collapse(pane);
+        self.renumber_after(pane);
+    }
+
+    fn collapse(&mut self, pane: usize) {
+        if let PaneLayout::Split { first, second, .. } = self {
+            if **first == PaneLayout::Pane(pane) {
+                *self = (**second).clone();
+            } else if **second == PaneLayout::Pane(pane) {
+                *self = (**first).clone();
+            } else {
+                first.collapse(pane);
+                second.collapse(pane);
+            }
+        }
+    }
+
+    fn renumber_after(&mut self, removed: usize) {
+        match self {
+            PaneLayout::Pane(index) if *index > removed => {
+                *index -= 1;
+            }
+            PaneLayout::Pane(_) => {}
+            PaneLayout::Split { first, second, .. } => {
+                first.renumber_after(removed);
+                second.renumber_after(removed);
+            }
+        }
+    }
+
+    /// Moves the closest split around `pane` by `delta` percent, growing the pane
+    /// for a positive `delta`. Returns false when there is no split to move.
+    fn resize_pane(&mut self, pane: usize, delta: i16) -> bool {
+        let PaneLayout::Split { ratio, first, second, .. } = self else {
+            return false;
+        };
+        let (child, sign) = match first.contains(pane) {
+            true => (first, 1),
+            false => (second, -1),
+        };
+        if child.resize_pane(pane, delta) {
+            return true;
+        }
+
+        let new_ratio = (*ratio as i16) + sign * delta;
+        *ratio = new_ratio.clamp(MIN_RATIO as i16, MAX_RATIO as i16) as u16;
+        true
+    }
+
+    fn areas(&self, area: PaneArea, areas: &mut Vec<(usize, PaneArea)>) {
+        let (x, y, width, height) = area;
+        match self {
+            PaneLayout::Pane(index) => areas.push((*index, area)),
+            PaneLayout::Split { direction: SplitDirection::Vertical, ratio, first, second } => {
+                let left = (width * (*ratio as u32)) / 100;
+                first.areas((x, y, left, height), areas);
+                second.areas((x + left, y, width - left, height), areas);
+            }
+            PaneLayout::Split { direction: SplitDirection::Horizontal, ratio, first, second } => {
+                let top = (height * (*ratio as u32)) / 100;
+                first.areas((x, y, width, top), areas);
+                second.areas((x, y + top, width, height - top), areas);
+            }
+        }
+    }
+
+    /// The pane next to `pane` in `direction`, the one sharing the longest edge
+    /// with it when there are several.
+    fn neighbour(&self, pane: usize, direction: FocusDirection) -> Option<usize> {
+        let mut areas = Vec::new();
+        self.areas((0, 0, GRID, GRID), &mut areas);
+        let (_, (x, y, width, height)) = *areas.iter().find(|(index, _)| *index == pane)?;
+
+        let overlap = |start: u32, length: u32, other_start: u32, other_length: u32| {
+            (start + length).min(other_start + other_length).saturating_sub(start.max(other_start))
+        };
+
+        areas
+            .iter()
+            .filter_map(|(index, (other_x, other_y, other_width, other_height))| {
+                let touches = match direction {
+                    FocusDirection::Left => other_x + other_width == x,
+                    FocusDirection::Right => x + width == *other_x,
+                    FocusDirection::Up => other_y + other_height == y,
+                    FocusDirection::Down => y + height == *other_y,
+                };
+                let shared = match direction {
+                    FocusDirection::Left | FocusDirection::Right =>
+                        overlap(y, height, *other_y, *other_height),
+                    FocusDirection::Up | FocusDirection::Down =>
+                        overlap(x, width, *other_x, *other_width),
+                };
+                match touches && shared > 0 {
+                    true => Some((*index, shared)),
+                    false => None,
+                }
+            })
+            .max_by_key(|(_, shared)| *shared)
+            .map(|(index, _)| index)
+    }
+}
+
+/// The layout of one tab while it is edited. Changes stay here until the
+/// editor is saved, `windows` tracks how many panes the layout has.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutEditor {
+    pub(super) tab: usize,
+    pub(super) layout: PaneLayout,
+    pub(super) focus: usize,
+    /// Commands of the panes, indexed like the panes.
+    pub(super) args: Vec<String>,
+}
+
+impl LayoutEditor {
+    pub fn new(tab: usize, layout: PaneLayout, args: Vec<String>) -> LayoutEditor {
+        LayoutEditor { tab, layout, focus: 0, args }
+    }
+
+    pub fn get_tab(&self) -> usize {
+        self.tab
+    }
+
+    pub fn get_layout(&self) -> &PaneLayout {
+        &self.layout
+    }
+
+    pub fn get_focus(&self) -> usize {
+        self.focus
+    }
+
+    pub fn get_args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Splits the focused pane and focuses the new, empty one.
+    pub fn split(&mut self, direction: SplitDirection) -> Result<(), String> {
+        if self.args.len() >= MAX_PANES {
+            return Err(format!("A tab cannot have more than {} windows.", MAX_PANES));
+        }
+
+        let new_pane = self.args.len();
+        self.layout.split_pane(self.focus, direction, new_pane);
+        self.args.push(String::new());
+        self.focus = new_pane;
+        Ok(())
+    }
+
+    /// Deletes the focused pane together with its command.
+    pub fn delete(&mut self) -> Result<(), String> {
+        if self.args.len() == 1 {
+            return Err(String::from("A tab needs at least one window."));
+        }
+
+        self.layout.remove_pane(self.focus);
+        self.args.remove(self.focus);
+        self.focus = self.focus.min(self.args.len() - 1);
+        Ok(())
+    }
+
+    /// Grows (positive `delta`) or shrinks the focused pane by `delta` percent.
+    pub fn resize(&mut self, delta: i16) {
+        self.layout.resize_pane(self.focus, delta);
+    }
+
+    pub fn move_focus(&mut self, direction: FocusDirection) {
+        if let Some(pane) = self.layout.neighbour(self.focus, direction) {
+            self.focus = pane;
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use super::text;
+use std::ops::Range;
+
+/// Text and cursor saved for undo and redo.
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+}
+
+/// A single line text field: cursor movement, selection, word jumps, undo and
+/// horizontal scrolling. Positions count grapheme clusters (see `text`).
+/// Clipboard access is left to the caller, `cut` and `selected_text` hand out
+/// the text and `insert_str` takes it back.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LineEditor {
+    text: String,
+    cursor: usize,
+    /// The other end of the selection, the cursor being one end.
+    anchor: Option<usize>,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// Consecutive typed characters are undone together.
+    typing: bool,
+    /// First grapheme shown when the text is wider than the input box.
+    offset: usize,
+}
+
+impl LineEditor {
+    pub fn new(text: &str) -> LineEditor {
+        let mut editor = LineEditor::default();
+        editor.set_text(text);
+        editor
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Replaces the text, puts the cursor at its end and forgets the history.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = text::grapheme_count(text);
+        self.anchor = None;
+        self.undo.clear();
+        self.redo.clear();
+        self.typing = false;
+        self.offset = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_text("");
+    }
+
+    /// Empties the field and returns its text.
+    pub fn take(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        self.clear();
+        text
+    }
+
+    /// Selected graphemes, `None` when nothing is selected.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        match anchor.cmp(&self.cursor) {
+            std::cmp::Ordering::Less => Some(anchor..self.cursor),
+            std::cmp::Ordering::Greater => Some(self.cursor..anchor),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    pub fn selected_text(&self) -> Option<&str> {
+        let selection = self.selection()?;
+        Some(&self.text[text::byte_range(&self.text, selection.start, selection.end)])
+    }
+
+    fn len(&self) -> usize {
+        text::grapheme_count(&self.text)
+    }
+
+    /// Moves the cursor to `position`, extending the selection when `select` is set.
+    fn move_to(&mut self, position: usize, select: bool) {
+        match select {
+            true if self.anchor.is_none() => {
+                self.anchor = Some(self.cursor);
+            }
+            true => {}
+            false => {
+                self.anchor = None;
+            }
+        }
+        self.cursor = position.min(self.len());
+        self.typing = false;
+    }
+
+    pub fn move_left(&mut self, select: bool) {
+        self.move_to(self.cursor.saturating_sub(1), select);
+    }
+
+    pub fn move_right(&mut self, select: bool) {
+        self.move_to(self.cursor + 1, select);
+    }
+
+    pub fn move_word_left(&mut self, select: bool) {
+        self.move_to(self.word_left(), select);
+    }
+
+    pub fn move_word_right(&mut self, select: bool) {
+        self.move_to(self.word_right(), select);
+    }
+
+    pub fn home(&mut self, select: bool) {
+        self.move_to(0, select);
+    }
+
+    pub fn end(&mut self, select: bool) {
+        self.move_to(self.len(), select);
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.len();
+        self.typing = false;
+    }
+
+    /// Start of the word before the cursor.
+    fn word_left(&self) -> usize {
+        let graphemes = text::graphemes(&self.text);
+
+        (1..self.cursor)
+            .rev()
+            .find(|&index| {
+                !text::is_whitespace(graphemes[index]) && text::is_whitespace(graphemes[index - 1])
+            })
+            .unwrap_or(0)
+    }
+
+    /// Start of the next word, or the end of the last word when there is none.
+    fn word_right(&self) -> usize {
+        let graphemes = text::graphemes(&self.text);
+        if self.cursor >= graphemes.len() {
+            return graphemes.len();
+        }
+
+        let next_word = (self.cursor + 1..graphemes.len()).find(|&index| {
+            text::is_whitespace(graphemes[index - 1]) && !text::is_whitespace(graphemes[index])
+        });
+        if let Some(index) = next_word {
+            return index;
+        }
+
+        let last_letter = graphemes
+            .iter()
+            .rev()
+            .position(|grapheme| grapheme.starts_with(|ch: char| ch.is_ascii_alphabetic()));
+
+        match last_letter {
+            Some(position) => (graphemes.len() - position).max(self.cursor),
+            None => graphemes.len(),
+        }
+    }
+
+    fn save_undo(&mut self) {
+        self.undo.push(Snapshot { text: self.text.clone(), cursor: self.cursor });
+        self.redo.clear();
+    }
+
+    /// Replaces the graphemes `range` with `replacement` and puts the cursor after it.
+    fn replace(&mut self, range: Range<usize>, replacement: &str) {
+        let bytes = text::byte_range(&self.text, range.start, range.end);
+        let start = bytes.start;
+        self.text.replace_range(bytes, replacement);
+        // Combining characters join the grapheme before them, so count again.
+        self.cursor = text::grapheme_count(&self.text[..start + replacement.len()]);
+        self.anchor = None;
+    }
+
+    pub fn insert_char(&mut self, ch: char) {
+        let selection = self.selection();
+        if !self.typing || selection.is_some() || ch.is_whitespace() {
+            self.save_undo();
+        }
+
+        let range = selection.unwrap_or(self.cursor..self.cursor);
+        self.replace(range, ch.encode_utf8(&mut [0; 4]));
+        self.typing = true;
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection.
+    pub fn insert_str(&mut self, text: &str) {
+        if text.is_empty() && self.selection().is_none() {
+            return;
+        }
+        self.save_undo();
+
+        let range = self.selection().unwrap_or(self.cursor..self.cursor);
+        self.replace(range, text);
+        self.typing = false;
+    }
+
+    /// Deletes the selection, or the graphemes between the cursor and `position`.
+    fn delete_to(&mut self, position: usize) {
+        let range = match self.selection() {
+            Some(selection) => selection,
+            None if position < self.cursor => position..self.cursor,
+            None => self.cursor..position.min(self.len()),
+        };
+        if range.is_empty() {
+            return;
+        }
+
+        self.save_undo();
+        self.replace(range, "");
+        self.typing = false;
+    }
+
+    pub fn backspace(&mut self) {
+        self.delete_to(self.cursor.saturating_sub(1));
+    }
+
+    pub fn delete_forward(&mut self) {
+        self.delete_to(self.cursor + 1);
+    }
+
+    pub fn delete_word_left(&mut self) {
+        self.delete_to(self.word_left());
+    }
+
+    pub fn delete_word_right(&mut self) {
+        self.delete_to(self.word_right());
+    }
+
+    /// Removes the selection and returns it.
+    pub fn cut(&mut self) -> Option<String> {
+        let selected = self.selected_text()?.to_string();
+        self.delete_to(self.cursor);
+        Some(selected)
+    }
+
+    pub fn undo(&mut self) {
+        self.restore(true);
+    }
+
+    pub fn redo(&mut self) {
+        self.restore(false);
+    }
+
+    fn restore(&mut self, undo: bool) {
+        let (from, to) = match undo {
+            true => (&mut self.undo, &mut self.redo),
+            false => (&mut self.redo, &mut self.undo),
+        };
+        let Some(snapshot) = from.pop() else {
+            return;
+        };
+
+        to.push(Snapshot { text: std::mem::replace(&mut self.text, snapshot.text), cursor: self.cursor });
+        self.cursor = snapshot.cursor;
+        self.anchor = None;
+        self.typing = false;
+    }
+
+    /// Scrolls so the cursor stays inside a box `width` columns wide and returns
+    /// the visible graphemes.
+    pub fn scroll_to_cursor(&mut self, width: usize) -> Range<usize> {
+        let graphemes = text::graphemes(&self.text);
+        let widths = graphemes
+            .iter()
+            .map(|grapheme| text::display_width(grapheme, 1))
+            .collect::<Vec<usize>>();
+
+        self.offset = self.offset.min(self.cursor);
+        // The cursor needs a free column after the text before it.
+        while self.offset < self.cursor && widths[self.offset..self.cursor].iter().sum::<usize>() >= width {
+            self.offset += 1;
+        }
+
+        let mut end = self.offset;
+        let mut used = 0;
+        while end < widths.len() && used + widths[end] <= width {
+            used += widths[end];
+            end += 1;
+        }
+
+        self.offset..end
+    }
+
+    /// Column of the cursor inside the input box after `scroll_to_cursor`.
+    pub fn cursor_column(&self) -> usize {
+        let start = text::byte_offset(&self.text, self.offset);
+        text::display_width(&self.text[start..], self.cursor.saturating_sub(self.offset))
+    }
+
+    /// Puts the cursor at a clicked column of the input box.
+    pub fn click(&mut self, column: usize) {
+        let start = text::byte_offset(&self.text, self.offset);
+        let position = self.offset + text::index_at_column(&self.text[start..], column);
+        self.move_to(position, false);
+    }
+}
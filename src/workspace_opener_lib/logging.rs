@@ -0,0 +1,143 @@
+use super::model::{ AppConfig, LogLevel, Settings };
+use log::LevelFilter;
+use simplelog::{ Config, WriteLogger };
+use std::{ fs::{ self, File, OpenOptions }, io::{ self, Write }, path::{ Path, PathBuf } };
+
+pub const LOG_FILE: &str = "output.log";
+
+/// Log destination and level after applying CLI flags on top of `Settings`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogOptions {
+    pub(super) path: PathBuf,
+    pub(super) level: LevelFilter,
+    pub(super) max_size: u64,
+    pub(super) max_files: u32,
+}
+
+impl LogOptions {
+    /// `log_file` and `log_level` come from the command line and win over the
+    /// config. Otherwise `debug_mode` raises the configured level to Debug.
+    pub fn resolve(
+        settings: &Settings,
+        config_path: &str,
+        log_file: Option<&str>,
+        log_level: Option<LogLevel>
+    ) -> LogOptions {
+        let path = log_file
+            .map(PathBuf::from)
+            .or_else(|| settings.log.path.as_ref().map(PathBuf::from))
+            .unwrap_or_else(|| default_log_path(config_path));
+
+        let level = match log_level {
+            Some(level) => level,
+            None if settings.debug_mode && settings.log.level < LogLevel::Debug => LogLevel::Debug,
+            None => settings.log.level,
+        };
+
+        LogOptions {
+            path,
+            level: level.to_level_filter(),
+            max_size: settings.log.max_size_kb * 1024,
+            max_files: settings.log.max_files,
+        }
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_level(&self) -> LevelFilter {
+        self.level
+    }
+}
+
+pub fn default_log_path(config_path: &str) -> PathBuf {
+    AppConfig::state_dir(config_path).join(LOG_FILE)
+}
+
+pub fn init(options: &LogOptions) -> Result<(), String> {
+    let file = RotatingFile::open(&options.path, options.max_size, options.max_files).map_err(|err|
+        format!("Cannot open the log file '{}': {}", options.path.display(), err)
+    )?;
+
+    WriteLogger::init(options.level, Config::default(), file).map_err(|err| err.to_string())
+}
+
+/// Appends to a log file and renames it to `<name>.1` (shifting older files up
+/// to `<name>.<max_files>`) once it grows past `max_size` bytes.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: u32,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_size: u64, max_files: u32) -> io::Result<RotatingFile> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+
+        let mut rotating_file = RotatingFile {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_size,
+            max_files,
+        };
+
+        if max_size > 0 && size >= max_size {
+            rotating_file.rotate()?;
+        }
+
+        Ok(rotating_file)
+    }
+
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        }
+
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.max_size > 0 && self.size > 0 && self.size + (buf.len() as u64) > self.max_size {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
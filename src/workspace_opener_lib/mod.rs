@@ -1,3 +1,26 @@
+#[cfg(feature = "tui")]
 pub mod api;
+#[cfg(feature = "tui")]
+pub mod app;
+pub mod cli;
+pub mod completions;
+#[cfg(feature = "tui")]
+pub mod crash;
+pub mod doctor;
+#[cfg(feature = "tui")]
+pub mod events;
+pub mod form;
+pub mod fuzzy;
+#[cfg(feature = "tui")]
+pub mod help;
 pub mod impls;
+#[cfg(feature = "tui")]
+pub mod keymap;
+pub mod layout;
+#[cfg(feature = "tui")]
+pub mod line_editor;
+pub mod logging;
 pub mod model;
+pub mod text;
+#[cfg(feature = "tui")]
+pub mod theme;
@@ -1,33 +1,35 @@
+use super::layout::PaneLayout;
 use serde::{ Deserialize, Serialize };
-use std::collections::VecDeque;
-use tui::{ style::Color, widgets::ListState };
+use std::collections::BTreeMap;
+
+/// Default path of the config file, relative to the working directory.
+pub const CONFIG: &str = "config.json";
+
+/// Themes that need no palette in `ThemeSettings::custom`.
+pub const BUILT_IN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub enum State {
-    Start,
-    Settings,
-    ChoosePreset,
-    CreatePreset,
-    EditPreset,
-    ChangeFieldName,
-    RunConfig,
-}
-#[derive(Debug)]
-pub enum InputMode {
-    Normal,
-    Input,
-    Edit,
-}
 pub enum WriteType {
     Create,
     Edit,
 }
+/// A change to the tabs or windows of a preset made from Edit Preset. Commands
+/// move together with their window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StructureEdit {
+    InsertBefore,
+    InsertAfter,
+    Duplicate,
+    Delete,
+    MoveLeft,
+    MoveRight,
+}
 #[derive(Debug, Clone, PartialEq)]
 pub enum PresetValue {
     Name(String),
     Tabs(u8),
     Windows(usize, u8),
     Args(usize, String),
+    Tags(Vec<String>),
     PresetInfo(PresetInfoValue),
 }
 #[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
@@ -52,26 +54,19 @@ pub enum ShellType {
     Fish,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Item {
-    pub(super) name: String,
-    pub(super) leading_state: State,
-    pub(super) preset_value: Option<PresetValue>,
-}
-pub struct Popup {
-    pub(super) active: bool,
-    pub(super) message: String,
-    pub(super) color: Color,
-}
-
-pub struct StatefulList {
-    pub(super) list_state: ListState,
-    pub(super) items: Vec<Item>,
-}
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
 pub struct AppConfig {
     pub(super) presets: Vec<Preset>,
     pub(super) settings: Settings,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) trash: Vec<TrashedPreset>,
+}
+/// A deleted preset, kept until it is restored or `Settings::trash_days` pass.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrashedPreset {
+    pub(super) preset: Preset,
+    /// Seconds since the Unix epoch.
+    pub(super) deleted_at: u64,
 }
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct PresetInfo {
@@ -82,6 +77,181 @@ pub struct PresetInfo {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Settings {
     pub(super) debug_mode: bool,
+    #[serde(default)]
+    pub(super) log: LogSettings,
+    #[serde(default)]
+    pub(super) keymap: KeymapSettings,
+    #[serde(default)]
+    pub(super) theme: ThemeSettings,
+    /// Days a deleted preset stays in the trash, 0 keeps it until it is purged by hand.
+    #[serde(default = "Settings::default_trash_days")]
+    pub(super) trash_days: u32,
+    /// Profile and shells the Create Preset wizard starts with.
+    #[serde(default)]
+    pub(super) preset_defaults: PresetInfo,
+    /// Leaves the app once a preset is launched, otherwise goes back to Choose Preset.
+    #[serde(default = "Settings::default_close_after_launch")]
+    pub(super) close_after_launch: bool,
+    /// Asks before a preset chosen in the list is launched.
+    #[serde(default)]
+    pub(super) confirm_before_launch: bool,
+    #[serde(default)]
+    pub(super) launcher: Launcher,
+    /// Folder the previous config is copied to before every save, no backup when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) backup_dir: Option<String>,
+}
+
+/// How the Windows Terminal command of a preset is opened.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Launcher {
+    /// Opens the tabs in a new window.
+    #[default]
+    Wt,
+    /// Adds the tabs to the most recently used window (`wt -w 0`).
+    WtTabs,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ThemeSettings {
+    /// `dark`, `light`, `high-contrast` or the name of a palette in `custom`.
+    pub(super) name: String,
+    pub(super) custom: BTreeMap<String, Palette>,
+}
+
+/// A custom theme. Roles left out keep the colors of the `base` theme (`dark` by default).
+/// Colors are names such as `lightblue`, `#rrggbb` or 256-color indices.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct Palette {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) base: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) background: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) border: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) edit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) highlight: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) highlight_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) selection: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) matched: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) success: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) debug_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) debug_input_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) debug_cursor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) debug_selection: Option<String>,
+}
+
+/// Named things a key can do, bound to keys by `Keymap`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Up,
+    Down,
+    First,
+    Last,
+    /// Opens the selected item: runs a preset, enters a menu or edits a field.
+    Launch,
+    Back,
+    Edit,
+    Delete,
+    Search,
+    Help,
+    /// Adds an empty tab or window before the selected one in Edit Preset.
+    InsertBefore,
+    /// Adds an empty tab or window after the selected one in Edit Preset.
+    InsertAfter,
+    /// Copies the selected tab or window, commands included, in Edit Preset.
+    Duplicate,
+    MoveLeft,
+    MoveRight,
+    /// Opens the layout editor for the selected tab in Edit Preset.
+    Layout,
+    /// Moves the selected choice field to its next option or flips the selected toggle.
+    Toggle,
+    SplitVertical,
+    SplitHorizontal,
+    Grow,
+    Shrink,
+    Submit,
+    Cancel,
+    /// Returns to the previous question of the Create Preset wizard.
+    PreviousStep,
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    SelectLeft,
+    SelectRight,
+    Home,
+    End,
+    SelectAll,
+    Backspace,
+    DeleteForward,
+    DeleteWordLeft,
+    DeleteWordRight,
+    Copy,
+    Cut,
+    Paste,
+    Undo,
+    Redo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    #[default]
+    Default,
+    /// Adds j/k, gg and G to the list navigation.
+    Vim,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct KeymapSettings {
+    pub(super) preset: KeymapPreset,
+    /// Replaces the keys of the listed actions, e.g. `"quit": ["q", "ctrl+q"]`.
+    pub(super) bindings: BTreeMap<Action, Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LogSettings {
+    /// Defaults to `state/output.log` next to the config when empty.
+    pub(super) path: Option<String>,
+    pub(super) level: LogLevel,
+    /// The log is rotated once it grows past this size, 0 disables rotation.
+    pub(super) max_size_kb: u64,
+    /// Amount of rotated files (`output.log.1`, `output.log.2`, ...) to keep.
+    pub(super) max_files: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -91,24 +261,18 @@ pub struct Preset {
     pub(super) windows: Vec<u8>,
     pub(super) args: Vec<String>,
     pub(super) preset_info: PresetInfo,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) tags: Vec<String>,
+    /// Pane layouts drawn in the layout editor, one per tab. `None` (or a missing
+    /// entry) keeps the arrangement `PaneLayout::default_for` gives.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) layouts: Vec<Option<PaneLayout>>,
 }
 
-pub struct App {
-    pub(super) state: State,
-    pub(super) previous_state: State,
-    pub(super) items: StatefulList,
-    pub(super) prompts: Vec<String>,
-    pub(super) input: String,
-    pub(super) selected_input: Vec<usize>,
-    pub(super) cursor_idx: usize,
-    pub(super) input_mode: InputMode,
-    pub(super) messages: Vec<String>,
-    pub(super) popup: Popup,
-    pub(super) current_preset: Option<Preset>,
-    pub(super) debug_mode: bool,
-}
-
-pub struct PresetCreationHelper {
-    pub(super) windows: VecDeque<u8>,
-    pub(super) max_windows: usize,
+/// Program and argv that open a preset, as produced by one of the launcher backends.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LaunchPlan {
+    pub(super) backend: String,
+    pub(super) program: String,
+    pub(super) args: Vec<String>,
 }
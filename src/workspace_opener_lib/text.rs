@@ -0,0 +1,53 @@
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Text in the input box is edited in grapheme clusters, so `e` followed by a
+/// combining accent or a flag emoji is a single step for the cursor. Indices
+/// passed to these functions count graphemes, not bytes or chars.
+pub fn graphemes(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Byte offset where the grapheme at `index` starts, `text.len()` past the end.
+pub fn byte_offset(text: &str, index: usize) -> usize {
+    text.grapheme_indices(true)
+        .nth(index)
+        .map(|(offset, _)| offset)
+        .unwrap_or(text.len())
+}
+
+/// Bytes of the graphemes `start..end`.
+pub fn byte_range(text: &str, start: usize, end: usize) -> Range<usize> {
+    byte_offset(text, start)..byte_offset(text, end)
+}
+
+/// Terminal columns taken by the first `index` graphemes. CJK characters and
+/// most emoji take two columns, combining marks none.
+pub fn display_width(text: &str, index: usize) -> usize {
+    text[..byte_offset(text, index)].width()
+}
+
+/// The grapheme index a click at `column` (relative to the start of the text)
+/// puts the cursor at. A click on the right half of a wide character still
+/// lands before it.
+pub fn index_at_column(text: &str, column: usize) -> usize {
+    let mut width = 0;
+
+    for (index, grapheme) in text.graphemes(true).enumerate() {
+        width += grapheme.width();
+        if width > column {
+            return index;
+        }
+    }
+
+    grapheme_count(text)
+}
+
+pub fn is_whitespace(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
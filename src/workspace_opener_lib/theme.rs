@@ -0,0 +1,277 @@
+use super::{ app::PopupKind, model::{ Palette, ThemeSettings, BUILT_IN_THEMES } };
+use std::{ env, str::FromStr };
+use tui::style::{ Color, Modifier, Style };
+
+/// Colors for every role drawn by `ui`, resolved from `ThemeSettings`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    text: Color,
+    background: Color,
+    border: Color,
+    edit: Color,
+    highlight: Color,
+    highlight_text: Color,
+    selection: Color,
+    matched: Color,
+    error: Color,
+    success: Color,
+    debug_state: Color,
+    debug_input_mode: Color,
+    debug_cursor: Color,
+    debug_selection: Color,
+    /// Set by `NO_COLOR`: every color is the terminal default and roles are told
+    /// apart by modifiers only.
+    monochrome: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            text: Color::White,
+            background: Color::Black,
+            border: Color::Reset,
+            edit: Color::Rgb(51, 153, 255),
+            highlight: Color::Rgb(51, 153, 255),
+            highlight_text: Color::White,
+            selection: Color::Cyan,
+            matched: Color::Yellow,
+            error: Color::Red,
+            success: Color::Green,
+            debug_state: Color::Red,
+            debug_input_mode: Color::LightYellow,
+            debug_cursor: Color::LightGreen,
+            debug_selection: Color::Magenta,
+            monochrome: false,
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            text: Color::Black,
+            background: Color::White,
+            border: Color::DarkGray,
+            edit: Color::Rgb(0, 90, 190),
+            highlight: Color::Rgb(170, 205, 255),
+            highlight_text: Color::Black,
+            selection: Color::Rgb(140, 220, 230),
+            matched: Color::Rgb(175, 85, 0),
+            error: Color::Rgb(190, 0, 0),
+            success: Color::Rgb(0, 125, 0),
+            debug_state: Color::Rgb(190, 0, 0),
+            debug_input_mode: Color::Rgb(140, 100, 0),
+            debug_cursor: Color::Rgb(0, 125, 0),
+            debug_selection: Color::Rgb(140, 0, 140),
+            monochrome: false,
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            text: Color::White,
+            background: Color::Black,
+            border: Color::White,
+            edit: Color::LightCyan,
+            highlight: Color::Yellow,
+            highlight_text: Color::Black,
+            selection: Color::White,
+            matched: Color::LightYellow,
+            error: Color::LightRed,
+            success: Color::LightGreen,
+            debug_state: Color::LightRed,
+            debug_input_mode: Color::LightYellow,
+            debug_cursor: Color::LightGreen,
+            debug_selection: Color::LightMagenta,
+            monochrome: false,
+        }
+    }
+
+    pub fn built_in(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    fn monochrome() -> Theme {
+        Theme {
+            text: Color::Reset,
+            background: Color::Reset,
+            border: Color::Reset,
+            edit: Color::Reset,
+            highlight: Color::Reset,
+            highlight_text: Color::Reset,
+            selection: Color::Reset,
+            matched: Color::Reset,
+            error: Color::Reset,
+            success: Color::Reset,
+            debug_state: Color::Reset,
+            debug_input_mode: Color::Reset,
+            debug_cursor: Color::Reset,
+            debug_selection: Color::Reset,
+            monochrome: true,
+        }
+    }
+
+    /// Like `Theme::new`, with colors turned off when the `NO_COLOR` variable is set.
+    pub fn from_settings(settings: &ThemeSettings) -> Result<Theme, String> {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Theme::new(settings, no_color)
+    }
+
+    /// Looks `settings.name` up in the custom palettes first, then in the built-in themes.
+    /// The settings are still validated when `no_color` wins over them.
+    pub fn new(settings: &ThemeSettings, no_color: bool) -> Result<Theme, String> {
+        let theme = match settings.custom.get(&settings.name) {
+            Some(palette) => {
+                let base = palette.base.as_deref().unwrap_or("dark");
+                let mut theme = Theme::built_in(base).ok_or_else(|| {
+                    format!("Unknown base theme '{}' of the theme '{}'.", base, settings.name)
+                })?;
+                theme.apply(palette).map_err(|err| format!("Theme '{}': {}", settings.name, err))?;
+                theme
+            }
+            None =>
+                Theme::built_in(&settings.name).ok_or_else(|| {
+                    format!(
+                        "Unknown theme '{}', expected one of {} or a custom theme.",
+                        settings.name,
+                        BUILT_IN_THEMES.join(", ")
+                    )
+                })?,
+        };
+
+        match no_color {
+            true => Ok(Theme::monochrome()),
+            false => Ok(theme),
+        }
+    }
+
+    fn apply(&mut self, palette: &Palette) -> Result<(), String> {
+        let roles = [
+            (&mut self.text, &palette.text),
+            (&mut self.background, &palette.background),
+            (&mut self.border, &palette.border),
+            (&mut self.edit, &palette.edit),
+            (&mut self.highlight, &palette.highlight),
+            (&mut self.highlight_text, &palette.highlight_text),
+            (&mut self.selection, &palette.selection),
+            (&mut self.matched, &palette.matched),
+            (&mut self.error, &palette.error),
+            (&mut self.success, &palette.success),
+            (&mut self.debug_state, &palette.debug_state),
+            (&mut self.debug_input_mode, &palette.debug_input_mode),
+            (&mut self.debug_cursor, &palette.debug_cursor),
+            (&mut self.debug_selection, &palette.debug_selection),
+        ];
+
+        for (color, value) in roles {
+            if let Some(value) = value {
+                *color = parse_color(value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn text(&self) -> Style {
+        Style::default().fg(self.text)
+    }
+
+    pub fn background(&self) -> Style {
+        Style::default().fg(self.text).bg(self.background)
+    }
+
+    pub fn border(&self) -> Style {
+        Style::default().fg(self.border)
+    }
+
+    pub fn edit(&self) -> Style {
+        Style::default().fg(self.edit)
+    }
+
+    /// The selected item of a list.
+    pub fn highlight(&self) -> Style {
+        match self.monochrome {
+            true => Style::default().add_modifier(Modifier::REVERSED),
+            false => Style::default().fg(self.highlight_text).bg(self.highlight),
+        }
+    }
+
+    /// Selected text in the input box.
+    pub fn selection(&self) -> Style {
+        match self.monochrome {
+            true => Style::default().add_modifier(Modifier::REVERSED),
+            false => Style::default().fg(self.highlight_text).bg(self.selection),
+        }
+    }
+
+    /// Characters matched by the preset filter.
+    pub fn matched(&self) -> Style {
+        match self.monochrome {
+            true => Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            false => Style::default().fg(self.matched).add_modifier(Modifier::BOLD),
+        }
+    }
+
+    pub fn popup(&self, kind: PopupKind) -> Style {
+        match kind {
+            PopupKind::Info => self.text(),
+            PopupKind::Success => Style::default().fg(self.success),
+            PopupKind::Error => Style::default().fg(self.error),
+        }
+    }
+
+    /// Colors of the State, InputMode, Cursor and Selected values shown in debug mode.
+    pub fn debug(&self) -> [Style; 4] {
+        [self.debug_state, self.debug_input_mode, self.debug_cursor, self.debug_selection].map(
+            |color| Style::default().fg(color).add_modifier(Modifier::BOLD)
+        )
+    }
+}
+
+/// Accepts color names (`red`, `lightblue`, `reset`, ...), `#rrggbb` and
+/// 256-color palette indices.
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    let invalid = || format!("Cannot parse color '{}'.", value);
+    let name = value.trim().to_lowercase().replace(['-', '_', ' '], "");
+
+    let color = match name.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        hex if hex.starts_with('#') => {
+            if hex.len() != 7 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+            let channel = |range: std::ops::Range<usize>| {
+                u8::from_str_radix(&hex[range], 16).map_err(|_| invalid())
+            };
+            Color::Rgb(channel(1..3)?, channel(3..5)?, channel(5..7)?)
+        }
+        index => Color::Indexed(u8::from_str(index).map_err(|_| invalid())?),
+    };
+
+    Ok(color)
+}
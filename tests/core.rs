@@ -0,0 +1,192 @@
+use std::fs;
+use workspace_opener::{
+    AppConfig,
+    FieldTarget,
+    FieldValue,
+    FormField,
+    Launcher,
+    Preset,
+    PresetInfo,
+    Settings,
+    ShellType,
+};
+
+#[test]
+fn core_api_loads_edits_and_launches_presets() {
+    let dir = std::env::temp_dir().join("workspace_opener_core_api");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let config_path = dir.join("config.json").to_string_lossy().to_string();
+
+    let preset = Preset::new(
+        String::from("Work"),
+        1,
+        vec![2],
+        vec![String::from("ls"), String::from("cargo test")],
+        PresetInfo::new(String::new(), ShellType::Powershell, ShellType::Bash)
+    );
+    assert!(preset.validate().is_empty());
+    let bash = Preset::new(
+        String::from("Bash"),
+        1,
+        vec![1],
+        vec![String::from("ls")],
+        PresetInfo::new(String::new(), ShellType::Bash, ShellType::Bash)
+    );
+    assert_eq!(bash.validate(), ["Init shell must be powershell, pwsh or cmd, found bash."]);
+    AppConfig::new(vec![preset], Settings::default()).save(&config_path).unwrap();
+
+    let mut app_config = AppConfig::load(&config_path).unwrap();
+    assert_eq!(app_config.get_presets().len(), 1);
+
+    let preset = app_config.get_mut_preset_by_name("Work").unwrap();
+    let mut fields = preset.form_fields();
+    let name = fields
+        .iter_mut()
+        .find(|field| field.get_target() == FieldTarget::Name)
+        .unwrap();
+    name.submit("Office").unwrap();
+    preset.apply_field(name).unwrap();
+    assert_eq!(preset.get_name(), "Office");
+    assert_eq!(preset.get_windows(), [2]);
+    assert_eq!(preset.get_args(), ["ls", "cargo test"]);
+    assert_eq!(preset.get_preset_info().get_target_shell(), &ShellType::Bash);
+
+    app_config.get_mut_settings().set_field(FieldTarget::Launcher, "wt-tabs").unwrap();
+    assert_eq!(app_config.get_settings().get_launcher(), Launcher::WtTabs);
+    app_config.save(&config_path).unwrap();
+
+    let app_config = AppConfig::load(&config_path).unwrap();
+    assert!(app_config.get_preset_by_name("Work").is_none());
+    let plan = app_config.create_launch_plan("Office").unwrap();
+    assert_eq!(plan.get_backend(), "wt-tabs");
+    assert!(plan.get_args().iter().any(|arg| arg.contains("cargo test")));
+    assert!(app_config.create_launch_plan("Work").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn preset_applies_form_fields() {
+    let mut preset = Preset::new(
+        String::from("Work"),
+        1,
+        vec![1],
+        vec![String::from("Not")],
+        PresetInfo::default()
+    );
+    let mut fields = preset.form_fields();
+
+    let args = fields
+        .iter_mut()
+        .find(|field| field.get_target() == FieldTarget::Args(0))
+        .unwrap();
+    args.submit("Changed").unwrap();
+    preset.apply_field(args).unwrap();
+    assert_eq!(preset.get_args(), ["Changed"]);
+
+    let windows = fields
+        .iter_mut()
+        .find(|field| field.get_target() == FieldTarget::Windows(0))
+        .unwrap();
+    assert!(windows.submit("5").is_err());
+    assert_eq!(windows.get_value().as_integer(), Some(1));
+
+    let tabs = fields
+        .iter_mut()
+        .find(|field| field.get_target() == FieldTarget::Tabs)
+        .unwrap();
+    assert!(tabs.submit("11").is_err());
+    assert_eq!(tabs.get_error(), Some("Enter a number from 1 to 10."));
+
+    // Args without a window get no field, validation reports them instead.
+    let broken = Preset::new(String::from("Broken"), 1, vec![], vec![String::from("ls")], PresetInfo::default());
+    let targets = broken
+        .form_fields()
+        .iter()
+        .map(|field| field.get_target())
+        .collect::<Vec<FieldTarget>>();
+    assert!(!targets.contains(&FieldTarget::Args(0)));
+    assert!(broken.validate().contains(&String::from("Expected 0 args (one per window), found 1.")));
+}
+
+#[test]
+fn fields_validate_their_input() {
+    let mut tabs = FormField::new(FieldTarget::Tabs, "Tabs", FieldValue::integer(10, 1, 10));
+    assert!(tabs.submit("11").is_err());
+    assert_eq!(tabs.get_error(), Some("Enter a number from 1 to 10."));
+    assert_eq!(tabs.get_value().as_integer(), Some(10));
+    tabs.submit(" 4 ").unwrap();
+    assert_eq!(tabs.item_name(), "Tabs: 4");
+    assert_eq!(tabs.get_error(), None);
+
+    let mut windows = FormField::new(
+        FieldTarget::Windows(0),
+        "Tab (#1), windows",
+        FieldValue::integer(4, 1, 4)
+    );
+    assert!(windows.submit("5").is_err());
+    assert!(windows.submit("four").is_err());
+
+    let mut name = FormField::new(FieldTarget::Name, "Name", FieldValue::text("Work", true));
+    assert_eq!(name.submit("  "), Err(String::from("Name cannot be empty.")));
+
+    let shells = vec![String::from("powershell"), String::from("cmd")];
+    let mut shell = FormField::new(
+        FieldTarget::InitShell,
+        "Init shell",
+        FieldValue::choice(shells, "cmd")
+    );
+    assert_eq!(shell.get_value().to_string(), "cmd");
+    assert!(shell.submit("zsh").unwrap_err().starts_with("Choose one of: powershell, cmd"));
+
+    let mut debug = FormField::new(FieldTarget::DebugMode, "Debug mode", FieldValue::Toggle(false));
+    debug.submit("true").unwrap();
+    assert_eq!(debug.get_value().as_toggle(), Some(true));
+    assert!(debug.submit("yes").is_err());
+}
+
+#[test]
+fn choices_and_toggles_cycle() {
+    let shells = vec![String::from("powershell"), String::from("pwsh"), String::from("cmd")];
+    let mut shell = FormField::new(
+        FieldTarget::InitShell,
+        "Init shell",
+        FieldValue::choice(shells, "powershell")
+    );
+    assert!(shell.get_value().is_selectable());
+    shell.cycle(false);
+    assert_eq!(shell.get_value().to_string(), "cmd");
+    shell.cycle(true);
+    shell.cycle(true);
+    assert_eq!(shell.get_value().options().map(|(_, selected)| selected), Some(1));
+
+    let mut debug = FormField::new(FieldTarget::DebugMode, "Debug mode", FieldValue::Toggle(false));
+    debug.reject("Not now.");
+    debug.cycle(true);
+    assert_eq!(debug.get_value().as_toggle(), Some(true));
+    assert_eq!(debug.get_error(), None);
+
+    let mut tabs = FieldValue::integer(3, 1, 10);
+    assert!(!tabs.is_selectable());
+    tabs.cycle(true);
+    assert_eq!(tabs.as_integer(), Some(3));
+}
+
+#[test]
+fn command_lists_keep_their_text() {
+    let mut args = FormField::new(FieldTarget::Args(0), "Arg", FieldValue::commands(""));
+    assert_eq!(args.get_value(), &FieldValue::Commands(Vec::new()));
+    args.submit("ls, cd Rust` Projects,pwd").unwrap();
+    assert_eq!(args.get_value().to_string(), "ls, cd Rust` Projects,pwd");
+    assert_eq!(args.submit("ls,,pwd"), Err(String::from("Command #2 is empty.")));
+
+    let mut path = FormField::new(
+        FieldTarget::Name,
+        "Path",
+        FieldValue::Path { path: String::new(), must_exist: true }
+    );
+    path.submit("").unwrap();
+    assert!(path.submit("/no/such/workspace_opener/path").is_err());
+    path.submit(".").unwrap();
+}
@@ -1,14 +1,39 @@
-use std::{ fs, io::Read };
+use clap::{ CommandFactory, Parser, ValueEnum };
+use log::LevelFilter;
+use std::{ fs, io::{ Read, Write }, path::Path, time::{ Duration, Instant } };
+use workspace_opener::workspace_opener_lib::app::{ InputMode, Item, Popup, PopupKind, State };
+use workspace_opener::workspace_opener_lib::cli::{ self, Cli };
+use workspace_opener::workspace_opener_lib::completions::CompletionShell;
+use workspace_opener::workspace_opener_lib::crash;
+use workspace_opener::workspace_opener_lib::doctor::{ self, CheckStatus };
+use workspace_opener::workspace_opener_lib::events::{ self, AppEvent, AppMessage };
+use workspace_opener::workspace_opener_lib::form::{ FieldTarget, FieldValue, FormField };
+use workspace_opener::workspace_opener_lib::fuzzy;
+use workspace_opener::workspace_opener_lib::help::{ self, HelpSection };
+use workspace_opener::workspace_opener_lib::keymap::{ KeyContext, KeyPress, Keymap };
+use workspace_opener::workspace_opener_lib::line_editor::LineEditor;
+use workspace_opener::workspace_opener_lib::layout::{
+    FocusDirection,
+    LayoutEditor,
+    PaneLayout,
+    SplitDirection,
+};
+use workspace_opener::workspace_opener_lib::text;
+use workspace_opener::workspace_opener_lib::theme::{ self, Theme };
+use workspace_opener::workspace_opener_lib::logging::{ LogOptions, RotatingFile };
 use workspace_opener::workspace_opener_lib::model::{
+    Action,
     AppConfig,
-    Item,
+    KeymapSettings,
+    LogLevel,
     Preset,
     PresetInfo,
     PresetInfoValue,
     PresetValue,
     Settings,
     ShellType,
-    State,
+    StructureEdit,
+    ThemeSettings,
     WriteType,
 };
 
@@ -31,17 +56,17 @@ fn preset_creation_basic() {
         preset_info
     );
 
-    let mut input: Vec<String> = vec![];
-
-    input.push(String::from("Test Preset"));
-    input.push(String::from("3"));
-    input.push(String::from("2"));
-    input.push(String::from("1"));
-    input.push(String::from("1"));
-    input.push(String::from("arg w1"));
-    input.push(String::from("arg w1"));
-    input.push(String::from("arg w2"));
-    input.push(String::from("arg w3"));
+    let input: Vec<String> = vec![
+        String::from("Test Preset"),
+        String::from("3"),
+        String::from("2"),
+        String::from("1"),
+        String::from("1"),
+        String::from("arg w1"),
+        String::from("arg w1"),
+        String::from("arg w2"),
+        String::from("arg w3")
+    ];
 
     let from_input = Preset::from_input(&input);
 
@@ -65,18 +90,18 @@ fn preset_creation_large() {
 
     let target = Preset::new(String::from("Test Preset"), 4, vec![2, 1, 1, 20], args, preset_info);
 
-    let mut input: Vec<String> = vec![];
-
-    input.push(String::from("Test Preset"));
-    input.push(String::from("4"));
-    input.push(String::from("2"));
-    input.push(String::from("1"));
-    input.push(String::from("1"));
-    input.push(String::from("20"));
-    input.push(String::from("arg w1"));
-    input.push(String::from("arg w1"));
-    input.push(String::from("arg w2"));
-    input.push(String::from("arg w3"));
+    let mut input: Vec<String> = vec![
+        String::from("Test Preset"),
+        String::from("4"),
+        String::from("2"),
+        String::from("1"),
+        String::from("1"),
+        String::from("20"),
+        String::from("arg w1"),
+        String::from("arg w1"),
+        String::from("arg w2"),
+        String::from("arg w3")
+    ];
 
     for n in 0..20 {
         input.push(format!("arg w4: {}", n));
@@ -110,9 +135,9 @@ fn preset_deletion() {
 
     let target = AppConfig::new(vec![], settings);
 
-    let items = vec![String::from("Test Preset")];
+    let items = [String::from("Test Preset")];
 
-    if let Err(err) = app_config.delete_preset_by_name(items.get(0).unwrap()) {
+    if let Err(err) = app_config.delete_preset_by_name(items.first().unwrap()) {
         panic!("{}", err);
     }
 
@@ -138,7 +163,7 @@ fn test_write_to_file_and_create() {
     let config_path = "test.json";
 
     let test_string =
-        "{\"presets\":[{\"name\":\"Test Preset\",\"tabs\":3,\"windows\":[2,1,1],\"args\":[\"arg w1\",\"arg w1\",\"arg w2\",\"arg w3\"],\"preset_info\":{\"wt_profile\":\"\",\"init_shell\":\"powershell\",\"target_shell\":\"powershell\"}}],\"settings\":{\"debug_mode\":false}}".to_string();
+        "{\"presets\":[{\"name\":\"Test Preset\",\"tabs\":3,\"windows\":[2,1,1],\"args\":[\"arg w1\",\"arg w1\",\"arg w2\",\"arg w3\"],\"preset_info\":{\"wt_profile\":\"\",\"init_shell\":\"powershell\",\"target_shell\":\"powershell\"}}],\"settings\":{\"debug_mode\":false,\"log\":{\"path\":null,\"level\":\"info\",\"max_size_kb\":1024,\"max_files\":3},\"keymap\":{\"preset\":\"default\",\"bindings\":{}},\"theme\":{\"name\":\"dark\",\"custom\":{}},\"trash_days\":30,\"preset_defaults\":{\"wt_profile\":\"\",\"init_shell\":\"powershell\",\"target_shell\":\"powershell\"},\"close_after_launch\":true,\"confirm_before_launch\":false,\"launcher\":\"wt\"}}".to_string();
 
     app_config
         .write_preset_to_file(&app_messages, WriteType::Create, config_path)
@@ -150,7 +175,7 @@ fn test_write_to_file_and_create() {
 
     file.read_to_string(&mut file_contents).expect("Failed to read file");
 
-    let assertion = file_contents == test_string.to_string();
+    let assertion = file_contents == test_string;
 
     fs::remove_file(config_path).expect("Failed to delete file");
 
@@ -183,7 +208,7 @@ fn test_write_to_file_and_delete() {
 
     app_config.delete_preset_by_name("Test Preset").expect("Failed to delete preset");
 
-    let test_string = "{\"presets\":[],\"settings\":{\"debug_mode\":false}}".to_string();
+    let test_string = "{\"presets\":[],\"settings\":{\"debug_mode\":false,\"log\":{\"path\":null,\"level\":\"info\",\"max_size_kb\":1024,\"max_files\":3},\"keymap\":{\"preset\":\"default\",\"bindings\":{}},\"theme\":{\"name\":\"dark\",\"custom\":{}},\"trash_days\":30,\"preset_defaults\":{\"wt_profile\":\"\",\"init_shell\":\"powershell\",\"target_shell\":\"powershell\"},\"close_after_launch\":true,\"confirm_before_launch\":false,\"launcher\":\"wt\"}}".to_string();
 
     app_config
         .write_preset_to_file(&app_messages, WriteType::Edit, config_path)
@@ -195,7 +220,7 @@ fn test_write_to_file_and_delete() {
 
     file.read_to_string(&mut file_contents).expect("Failed to read file");
 
-    let assertion = file_contents == test_string.to_string();
+    let assertion = file_contents == test_string;
 
     fs::remove_file(config_path).expect("Failed to delete file");
 
@@ -204,6 +229,94 @@ fn test_write_to_file_and_delete() {
     }
 }
 
+#[test]
+fn trash_restores_and_purges_presets() {
+    use std::time::{ Duration, UNIX_EPOCH };
+
+    let day = Duration::from_secs(24 * 60 * 60);
+    let deleted = UNIX_EPOCH + day * 1000;
+
+    let preset = |name: &str| {
+        Preset::from_input(&[name.to_string(), String::from("1"), String::from("1"), String::from("ls")])
+    };
+    let name = String::from("First");
+
+    let mut app_config = AppConfig::default();
+    app_config.add_presets(vec![preset("First"), preset("Second")]);
+
+    app_config.trash_preset_by_name(&name, deleted).unwrap();
+    assert!(app_config.get_preset_by_name(&name).is_none());
+    assert_eq!(app_config.get_trash()[0].get_preset(), &preset("First"));
+    assert_eq!(app_config.get_trash()[0].days_since_deleted(deleted + day * 3), 3);
+    assert!(app_config.trash_preset_by_name("Missing", deleted).is_err());
+
+    let json = serde_json::to_string(&app_config).unwrap();
+    assert!(json.contains("\"trash\":[{\"preset\":"));
+    assert_eq!(serde_json::from_str::<AppConfig>(&json).unwrap(), app_config);
+
+    // A preset with the same name blocks the restore.
+    app_config.add_presets(vec![preset("First")]);
+    assert!(app_config.restore_preset(0).unwrap_err().contains("ALREADY EXISTS"));
+    app_config.delete_preset_by_name(&name).unwrap();
+    assert_eq!(app_config.restore_preset(0), Ok(name.clone()));
+    assert!(app_config.get_trash().is_empty());
+    assert!(app_config.restore_preset(0).is_err());
+
+    app_config.trash_preset_by_name(&name, deleted).unwrap();
+    app_config.trash_preset_by_name("Second", deleted + day * 20).unwrap();
+    assert_eq!(app_config.purge_expired_trash(deleted + day * 29), 0);
+    assert_eq!(app_config.purge_expired_trash(deleted + day * 30), 1);
+    assert_eq!(app_config.get_trash()[0].get_preset(), &preset("Second"));
+
+    let mut settings = Settings::default();
+    settings.set_field(FieldTarget::TrashDays, "0").unwrap();
+    assert!(settings.set_field(FieldTarget::TrashDays, "soon").is_err());
+    let mut keep_forever = AppConfig::new(vec![], settings);
+    keep_forever.add_presets(vec![preset("First")]);
+    keep_forever.trash_preset_by_name(&name, deleted).unwrap();
+    assert_eq!(keep_forever.purge_expired_trash(deleted + day * 10000), 0);
+
+    app_config.purge_preset(0).unwrap();
+    assert!(app_config.get_trash().is_empty());
+}
+
+#[test]
+fn settings_are_typed_and_backed_up() {
+    let mut settings = Settings::default();
+    settings.set_field(FieldTarget::Launcher, "wt-tabs").unwrap();
+    assert!(settings.set_field(FieldTarget::Launcher, "alacritty").is_err());
+    assert!(settings.set_field(FieldTarget::Theme, "solarized").is_err());
+    settings.set_field(FieldTarget::Theme, "light").unwrap();
+    assert!(settings.set_field(FieldTarget::LogLevel, "verbose").is_err());
+    settings.set_field(FieldTarget::CloseAfterLaunch, "false").unwrap();
+    assert_eq!(
+        settings.set_field(FieldTarget::BackupDir, "no_such_backup_dir"),
+        Err(String::from("'no_such_backup_dir' does not exist."))
+    );
+
+    let backup_dir = std::env::temp_dir().join("workspace_opener_backup_test");
+    fs::create_dir_all(&backup_dir).unwrap();
+    settings.set_field(FieldTarget::BackupDir, &backup_dir.to_string_lossy()).unwrap();
+
+    let mut app_config = AppConfig::new(vec![Preset::default()], settings);
+    let plan = app_config.create_launch_plan("Test Preset").unwrap();
+    assert!(plan.get_args()[0].starts_with("wt.exe -w 0 powershell"));
+
+    let config_path = "test_settings_backup.json";
+    app_config.write_preset_to_file(&[], WriteType::Edit, config_path).unwrap();
+    assert!(!backup_dir.join("test_settings_backup.json.bak").exists());
+    app_config.delete_preset_by_name("Test Preset").unwrap();
+    app_config.write_preset_to_file(&[], WriteType::Edit, config_path).unwrap();
+
+    let backup = fs::read_to_string(backup_dir.join("test_settings_backup.json.bak")).unwrap();
+    assert!(backup.contains("Test Preset"));
+    assert!(backup.contains("\"launcher\":\"wt-tabs\""));
+    assert!(!fs::read_to_string(config_path).unwrap().contains("Test Preset"));
+
+    fs::remove_file(config_path).unwrap();
+    fs::remove_dir_all(backup_dir).unwrap();
+}
+
 #[test]
 fn test_into_items() {
     let preset_info = PresetInfo::default();
@@ -222,104 +335,141 @@ fn test_into_items() {
     );
 
     let items = preset.into_items();
-
-    let mut target = vec![];
-
-    let name = Item::new(
-        format!("Name: {}", "Test Preset"),
-        State::ChangeFieldName,
-        Some(PresetValue::Name("Test Preset".to_string()))
-    );
-
-    target.push(name);
-
-    let tabs = Item::new(
-        format!("Tabs: {}", "3".to_string()),
-        State::ChangeFieldName,
-        Some(PresetValue::Tabs(3))
-    );
-
-    target.push(tabs);
-
-    let window = Item::new(
-        format!("Tab (#{}), windows: {}", 1, 2),
-        State::ChangeFieldName,
-        Some(PresetValue::Windows(0, 2))
-    );
-
-    target.push(window);
-
-    let window = Item::new(
-        format!("Tab (#{}), windows: {}", 2, 1),
-        State::ChangeFieldName,
-        Some(PresetValue::Windows(1, 1))
-    );
-
-    target.push(window);
-
-    let window = Item::new(
-        format!("Tab (#{}), windows: {}", 3, 1),
-        State::ChangeFieldName,
-        Some(PresetValue::Windows(2, 1))
-    );
-
-    target.push(window);
-
-    let arg = Item::new(
-        format!("Tab (#{}), window (#{}), Arg: {}", 1, 1, "arg w1"),
-        State::ChangeFieldName,
-        Some(PresetValue::Args(0, "arg w1".to_string()))
-    );
-
-    target.push(arg);
-
-    let arg = Item::new(
-        format!("Tab (#{}), window (#{}), Arg: {}", 1, 2, "arg w1"),
-        State::ChangeFieldName,
-        Some(PresetValue::Args(1, "arg w1".to_string()))
-    );
-
-    target.push(arg);
-
-    let arg = Item::new(
-        format!("Tab (#{}), window (#{}), Arg: {}", 2, 1, "arg w2"),
-        State::ChangeFieldName,
-        Some(PresetValue::Args(2, "arg w2".to_string()))
-    );
-
-    target.push(arg);
-
-    let arg = Item::new(
-        format!("Tab (#{}), window (#{}), Arg: {}", 3, 1, "arg w3"),
-        State::ChangeFieldName,
-        Some(PresetValue::Args(3, "arg w3".to_string()))
-    );
-
-    target.push(arg);
-
-    let wt_profile = Item::new(
-        format!("Windows terminal profile name: {}", ""),
-        State::ChangeFieldName,
-        Some(PresetValue::PresetInfo(PresetInfoValue::WtProfile("".to_string())))
-    );
-
-    target.push(wt_profile);
-
-    let init_shell = Item::new(
-        format!("Init shell (powershell/pwsh/cmd): {}", "powershell".to_string()),
-        State::ChangeFieldName,
-        Some(PresetValue::PresetInfo(PresetInfoValue::InitShell(ShellType::WindowsPowershell)))
-    );
-
-    target.push(init_shell);
-
-    let target_shell = Item::new(
-        format!("Target shell (powershell/pwsh/cmd/bash/zsh/fish): {}", "powershell".to_string()),
-        State::ChangeFieldName,
-        Some(PresetValue::PresetInfo(PresetInfoValue::TargetShell(ShellType::WindowsPowershell)))
-    );
-
-    target.push(target_shell);
+    let init_shells = vec![String::from("powershell"), String::from("pwsh"), String::from("cmd")];
+
+    let target = vec![
+        Item::new(
+            format!("Name: {}", "Test Preset"),
+            State::ChangeFieldName,
+            Some(FormField::new(FieldTarget::Name, "Name", FieldValue::text("Test Preset", true)))
+        ),
+        Item::new(
+            format!("Tabs: {}", "3"),
+            State::ChangeFieldName,
+            Some(FormField::new(FieldTarget::Tabs, "Tabs", FieldValue::integer(3, 1, 10)))
+        ),
+        Item::new(
+            format!("Tab (#{}), windows: {}", 1, 2),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Windows(0),
+                    "Tab (#1), windows",
+                    FieldValue::integer(2, 1, 4)
+                )
+            )
+        ),
+        Item::new(
+            format!("Tab (#{}), windows: {}", 2, 1),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Windows(1),
+                    "Tab (#2), windows",
+                    FieldValue::integer(1, 1, 4)
+                )
+            )
+        ),
+        Item::new(
+            format!("Tab (#{}), windows: {}", 3, 1),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Windows(2),
+                    "Tab (#3), windows",
+                    FieldValue::integer(1, 1, 4)
+                )
+            )
+        ),
+        Item::new(
+            format!("Tab (#{}), window (#{}), Arg: {}", 1, 1, "arg w1"),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Args(0),
+                    "Tab (#1), window (#1), Arg",
+                    FieldValue::commands("arg w1")
+                )
+            )
+        ),
+        Item::new(
+            format!("Tab (#{}), window (#{}), Arg: {}", 1, 2, "arg w1"),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Args(1),
+                    "Tab (#1), window (#2), Arg",
+                    FieldValue::commands("arg w1")
+                )
+            )
+        ),
+        Item::new(
+            format!("Tab (#{}), window (#{}), Arg: {}", 2, 1, "arg w2"),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Args(2),
+                    "Tab (#2), window (#1), Arg",
+                    FieldValue::commands("arg w2")
+                )
+            )
+        ),
+        Item::new(
+            format!("Tab (#{}), window (#{}), Arg: {}", 3, 1, "arg w3"),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Args(3),
+                    "Tab (#3), window (#1), Arg",
+                    FieldValue::commands("arg w3")
+                )
+            )
+        ),
+        Item::new(
+            format!("Windows terminal profile name: {}", ""),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::WtProfile,
+                    "Windows terminal profile name",
+                    FieldValue::text("", false)
+                )
+            )
+        ),
+        Item::new(
+            format!("Init shell (powershell/pwsh/cmd): {}", "powershell"),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::InitShell,
+                    "Init shell (powershell/pwsh/cmd)",
+                    FieldValue::choice(init_shells, "powershell")
+                )
+            )
+        ),
+        Item::new(
+            format!("Target shell (powershell/pwsh/cmd/bash/zsh/fish): {}", "powershell"),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::TargetShell,
+                    "Target shell (powershell/pwsh/cmd/bash/zsh/fish)",
+                    FieldValue::choice(ShellType::names(), "powershell")
+                )
+            )
+        ),
+        Item::new(
+            format!("Tags (comma separated): {}", ""),
+            State::ChangeFieldName,
+            Some(
+                FormField::new(
+                    FieldTarget::Tags,
+                    "Tags (comma separated)",
+                    FieldValue::text("", false)
+                )
+            )
+        )
+    ];
 
     for (index, item) in items.iter().enumerate() {
         assert_eq!(item, target.get(index).unwrap());
@@ -456,6 +606,57 @@ fn test_preset_change_field_value() {
     assert_eq!(preset, target);
 }
 
+#[test]
+fn tabs_and_windows_keep_their_commands() {
+    let layout = |windows: &[u8], args: &[&str]| {
+        Preset::new(
+            String::from("Layout"),
+            windows.len() as u8,
+            windows.to_vec(),
+            args.iter().map(|arg| arg.to_string()).collect(),
+            PresetInfo::default()
+        )
+    };
+    let mut preset = layout(&[2, 1, 1], &["a1", "a2", "b1", "c1"]);
+
+    // Tabs take their windows and commands along.
+    assert_eq!(preset.edit_tab(0, StructureEdit::MoveRight), Ok(1));
+    assert_eq!(preset, layout(&[1, 2, 1], &["b1", "a1", "a2", "c1"]));
+    assert_eq!(preset.edit_tab(1, StructureEdit::Duplicate), Ok(2));
+    assert_eq!(preset, layout(&[1, 2, 2, 1], &["b1", "a1", "a2", "a1", "a2", "c1"]));
+    assert_eq!(preset.edit_tab(3, StructureEdit::MoveLeft), Ok(2));
+    assert_eq!(preset, layout(&[1, 2, 1, 2], &["b1", "a1", "a2", "c1", "a1", "a2"]));
+    assert_eq!(preset.edit_tab(1, StructureEdit::Delete), Ok(1));
+    assert_eq!(preset, layout(&[1, 1, 2], &["b1", "c1", "a1", "a2"]));
+    assert_eq!(preset.edit_tab(1, StructureEdit::InsertBefore), Ok(1));
+    assert_eq!(preset, layout(&[1, 1, 1, 2], &["b1", "", "c1", "a1", "a2"]));
+    assert_eq!(preset.edit_tab(3, StructureEdit::InsertAfter), Ok(4));
+    assert_eq!(preset, layout(&[1, 1, 1, 2, 1], &["b1", "", "c1", "a1", "a2", ""]));
+    assert_eq!(preset.edit_tab(0, StructureEdit::MoveLeft), Ok(0));
+
+    // Windows stay inside their tab.
+    assert_eq!(preset.edit_window(3, StructureEdit::MoveRight), Ok(4));
+    assert_eq!(preset, layout(&[1, 1, 1, 2, 1], &["b1", "", "c1", "a2", "a1", ""]));
+    assert_eq!(preset.edit_window(4, StructureEdit::MoveRight), Ok(4));
+    assert_eq!(preset.edit_window(2, StructureEdit::Duplicate), Ok(3));
+    assert_eq!(preset.edit_window(4, StructureEdit::InsertBefore), Ok(4));
+    assert_eq!(preset, layout(&[1, 1, 2, 3, 1], &["b1", "", "c1", "c1", "", "a2", "a1", ""]));
+    assert_eq!(preset.edit_window(6, StructureEdit::Delete), Ok(5));
+    assert_eq!(preset, layout(&[1, 1, 2, 2, 1], &["b1", "", "c1", "c1", "", "a2", ""]));
+
+    assert!(preset.edit_window(0, StructureEdit::Delete).is_err());
+    assert!(preset.edit_window(9, StructureEdit::Delete).is_err());
+    for _ in 0..2 {
+        preset.edit_window(2, StructureEdit::InsertAfter).unwrap();
+    }
+    assert!(preset.edit_window(2, StructureEdit::Duplicate).is_err());
+    for _ in 0..5 {
+        preset.edit_tab(0, StructureEdit::Duplicate).unwrap();
+    }
+    assert!(preset.edit_tab(0, StructureEdit::InsertAfter).is_err());
+    assert!(preset.validate().is_empty());
+}
+
 #[test]
 fn wt_command_default() {
     let preset = Preset::default();
@@ -625,3 +826,909 @@ fn wt_command_1tabs_4windows() {
 
     assert_eq!(app_config.create_wt_command("Test Preset").unwrap().1, target);
 }
+
+#[test]
+fn cli_create_set_show_delete() {
+    let config_path = "test_cli.json";
+    AppConfig::default()
+        .write_preset_to_file(&[], WriteType::Edit, config_path)
+        .expect("Failed to write file");
+
+    let run = |args: &[&str]| -> Result<String, String> {
+        let cli = Cli::try_parse_from(
+            ["workspace_opener", "--config", config_path].iter().chain(args.iter())
+        ).map_err(|err| err.to_string())?;
+        let mut out = Vec::new();
+        cli::execute(cli.command.unwrap(), &cli.global, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    };
+
+    run(
+        &[
+            "create",
+            "--name",
+            "Test Preset",
+            "--tabs",
+            "3",
+            "--windows",
+            "2,1,1",
+            "--cmd",
+            "arg w1",
+            "--cmd",
+            "arg w1",
+            "--cmd",
+            "arg w2",
+            "--cmd",
+            "arg w3",
+            "--tag",
+            "work",
+        ]
+    ).expect("Failed to create preset");
+
+    let duplicate = run(
+        &["create", "--name", "Test Preset", "--tabs", "1", "--windows", "1", "--cmd", "ls"]
+    );
+    let missing_cmds = run(&["create", "--name", "Other", "--tabs", "1", "--windows", "2"]);
+
+    run(&["set", "Test Preset", "tab.2.window.1", "ls, pwd"]).expect("Failed to set arg");
+    run(&["set", "Test Preset", "target_shell", "zsh"]).expect("Failed to set target shell");
+    run(&["set", "Test Preset", "tags", "work, rust,"]).expect("Failed to set tags");
+    let invalid_field = run(&["set", "Test Preset", "tab.4.windows", "2"]);
+
+    let list = run(&["list"]).expect("Failed to list presets");
+    let show = run(&["show", "Test Preset"]).expect("Failed to show preset");
+
+    let deleted = run(&["delete", "Test Preset"]).expect("Failed to delete preset");
+    let after_delete = run(&["list"]).expect("Failed to list presets");
+    let trashed = AppConfig::load(config_path).expect("Failed to load config");
+
+    run(&["create", "--name", "Other", "--tabs", "1", "--windows", "1", "--cmd", "ls"]).expect(
+        "Failed to create preset"
+    );
+    run(&["delete", "Other", "--permanent"]).expect("Failed to delete preset");
+    let after_permanent = AppConfig::load(config_path).expect("Failed to load config");
+
+    fs::remove_file(config_path).expect("Failed to delete file");
+
+    assert_eq!(deleted, "Preset 'Test Preset' moved to the trash.\n");
+    assert_eq!(trashed.get_trash().len(), 1);
+    assert_eq!(trashed.get_trash()[0].get_preset().get_name(), "Test Preset");
+    assert!(after_permanent.get_preset_by_name("Other").is_none());
+    assert_eq!(after_permanent.get_trash().len(), 1);
+
+    assert!(duplicate.is_err());
+    assert!(missing_cmds.is_err());
+    assert!(invalid_field.is_err());
+    assert_eq!(list, "Test Preset\n");
+    assert!(show.contains("Tab (#2), window (#1), Arg: ls, pwd\n"));
+    assert!(show.contains("Target shell (powershell/pwsh/cmd/bash/zsh/fish): zsh\n"));
+    assert!(show.contains("Tags (comma separated): work, rust\n"));
+    assert_eq!(after_delete, "");
+}
+
+#[test]
+fn fuzzy_match_prefers_word_starts() {
+    let matched = fuzzy::fuzzy_match("wo", "Web Opener").unwrap();
+    assert_eq!(matched.get_indices(), &[0, 4]);
+
+    let matched = fuzzy::fuzzy_match("OPE", "web opener").unwrap();
+    assert_eq!(matched.get_indices(), &[4, 5, 6]);
+
+    let consecutive = fuzzy::fuzzy_match("api", "api server").unwrap();
+    let scattered = fuzzy::fuzzy_match("api", "a pretty icon").unwrap();
+    assert!(consecutive.get_score() > scattered.get_score());
+
+    assert!(fuzzy::fuzzy_match("xyz", "web opener").is_none());
+    assert_eq!(fuzzy::fuzzy_match("", "web opener").unwrap().get_score(), 0);
+}
+
+#[test]
+fn preset_fuzzy_match_searches_tags_and_commands() {
+    let preset_info = PresetInfo::default();
+    let mut app_config = AppConfig::new(
+        vec![
+            Preset::new(
+                String::from("Backend"),
+                1,
+                vec![1],
+                vec![String::from("cd ~/api, cargo watch")],
+                preset_info.clone()
+            ),
+            Preset::new(String::from("Cargo"), 1, vec![1], vec![String::from("ls")], preset_info)
+        ],
+        Settings::default()
+    );
+    let config_path = "test_fuzzy.json";
+    app_config.write_preset_to_file(&[], WriteType::Edit, config_path).unwrap();
+
+    let cli = Cli::try_parse_from(
+        ["workspace_opener", "--config", config_path, "set", "Cargo", "tags", "rust, tools"]
+    ).unwrap();
+    cli::execute(cli.command.unwrap(), &cli.global, &mut Vec::new()).unwrap();
+    let app_config = AppConfig::load(config_path).unwrap();
+    fs::remove_file(config_path).expect("Failed to delete file");
+
+    let backend = app_config.get_preset_by_name("Backend").unwrap();
+    let cargo = app_config.get_preset_by_name("Cargo").unwrap();
+
+    assert_eq!(cargo.get_tags(), &["rust", "tools"]);
+
+    let by_command = backend.fuzzy_match("watch").unwrap();
+    assert!(by_command.get_indices().is_empty());
+
+    let by_name = cargo.fuzzy_match("cargo").unwrap();
+    let by_arg = backend.fuzzy_match("cargo").unwrap();
+    assert_eq!(by_name.get_indices(), &[0, 1, 2, 3, 4]);
+    assert!(by_name.get_score() > by_arg.get_score());
+
+    assert!(cargo.fuzzy_match("rust").is_some());
+    assert!(backend.fuzzy_match("rust").is_none());
+}
+
+#[test]
+fn default_pane_layouts() {
+    assert_eq!(PaneLayout::default_for(1), PaneLayout::Pane(0));
+    assert_eq!(
+        PaneLayout::default_for(2),
+        PaneLayout::split(SplitDirection::Vertical, 50, PaneLayout::Pane(0), PaneLayout::Pane(1))
+    );
+    assert_eq!(PaneLayout::default_for(3).panes(), vec![0, 1, 2]);
+    // 1 | 2
+    // --+--
+    // 4 | 3
+    assert_eq!(PaneLayout::default_for(4).panes(), vec![0, 3, 1, 2]);
+
+    let preset = Preset::default();
+    assert_eq!(preset.get_tab_args(0).unwrap(), &["arg w1", "arg w1"]);
+    assert_eq!(preset.get_tab_args(2).unwrap(), &["arg w3"]);
+    assert!(preset.get_tab_args(3).is_none());
+}
+
+#[test]
+fn layout_editor_splits_resizes_and_deletes_panes() {
+    use PaneLayout::Pane;
+
+    let mut editor = LayoutEditor::new(0, Pane(0), vec![String::from("ls")]);
+    editor.split(SplitDirection::Vertical).unwrap();
+    editor.split(SplitDirection::Horizontal).unwrap();
+    assert_eq!(editor.get_focus(), 2);
+    assert_eq!(editor.get_args(), &["ls", "", ""]);
+
+    // The closest split around the focused pane moves.
+    editor.resize(5);
+    let stacked = |ratio| PaneLayout::split(SplitDirection::Horizontal, ratio, Pane(1), Pane(2));
+    assert_eq!(
+        editor.get_layout(),
+        &PaneLayout::split(SplitDirection::Vertical, 50, Pane(0), stacked(45))
+    );
+    for _ in 0..20 {
+        editor.resize(-5);
+    }
+    assert_eq!(
+        editor.get_layout(),
+        &PaneLayout::split(SplitDirection::Vertical, 50, Pane(0), stacked(90))
+    );
+
+    editor.move_focus(FocusDirection::Up);
+    assert_eq!(editor.get_focus(), 1);
+    editor.move_focus(FocusDirection::Left);
+    editor.move_focus(FocusDirection::Left);
+    assert_eq!(editor.get_focus(), 0);
+    editor.move_focus(FocusDirection::Right);
+    assert_eq!(editor.get_focus(), 1);
+
+    editor.split(SplitDirection::Vertical).unwrap();
+    assert!(editor.split(SplitDirection::Vertical).is_err());
+
+    // Panes after the deleted one are renumbered, their commands move along.
+    editor.move_focus(FocusDirection::Left);
+    editor.delete().unwrap();
+    assert_eq!(editor.get_args(), &["ls", "", ""]);
+    assert!(editor.get_layout().fits(3));
+    editor.delete().unwrap();
+    editor.delete().unwrap();
+    assert!(editor.delete().is_err());
+    assert_eq!(editor.get_layout(), &Pane(0));
+}
+
+#[test]
+fn layouts_are_stored_on_the_preset() {
+    use PaneLayout::Pane;
+
+    let mut app_config = AppConfig::default();
+    let mut preset = Preset::new(
+        String::from("Layout"),
+        2,
+        vec![1, 2],
+        vec![String::from("ls"), String::from("pwd"), String::from("top")],
+        PresetInfo::default()
+    );
+    let layout = PaneLayout::split(
+        SplitDirection::Horizontal,
+        60,
+        PaneLayout::split(SplitDirection::Vertical, 30, Pane(0), Pane(1)),
+        Pane(2)
+    );
+    let args = vec![String::from("pwd"), String::from("top"), String::from("htop")];
+    preset.apply_layout(&LayoutEditor::new(1, layout.clone(), args)).unwrap();
+    assert_eq!(preset.layout(1), layout);
+    assert_eq!(preset.layout(0), Pane(0));
+    assert!(preset.validate().is_empty());
+
+    // The layout follows its tab, adding a window brings back the default one.
+    preset.edit_tab(1, StructureEdit::MoveLeft).unwrap();
+    assert_eq!(preset.layout(0), layout);
+    preset.edit_window(1, StructureEdit::MoveRight).unwrap();
+    assert_eq!(preset.layout(0).panes(), vec![0, 2, 1]);
+
+    app_config.add_presets(vec![preset.clone()]);
+    let target =
+        "wt.exe powershell -NoExit -Command 'pwd\\;'`; sp -H -s .40 powershell -NoExit -Command 'htop\\;'`; fp -t 0`; sp -V -s .70 powershell -NoExit -Command 'top\\;'`; nt powershell -NoExit -Command 'ls\\;'";
+    assert_eq!(app_config.create_wt_command("Layout").unwrap().1, target);
+
+    let json = serde_json::to_string(&preset).unwrap();
+    assert!(json.contains("\"layouts\":[{\"split\""));
+    assert_eq!(serde_json::from_str::<Preset>(&json).unwrap(), preset);
+
+    preset.edit_window(0, StructureEdit::InsertAfter).unwrap();
+    assert_eq!(preset.layout(0), PaneLayout::default_for(4));
+    assert!(!serde_json::to_string(&preset).unwrap().contains("layouts"));
+}
+
+#[test]
+fn out_of_range_split_ratios_are_rejected() {
+    let config_path = "test_layout_ratios.json";
+    let preset = Preset::new(
+        String::from("Layout"),
+        2,
+        vec![2, 3],
+        ["ls", "pwd", "top", "htop", "btop"].map(String::from).to_vec(),
+        PresetInfo::default()
+    );
+    let mut config = serde_json::to_value(AppConfig::new(vec![preset], Settings::default())).unwrap();
+    // A ratio above 100 would underflow `100 - ratio`, 0 would give `-s .100`.
+    config["presets"][0]["layouts"] = serde_json::json!([
+        { "split": { "direction": "vertical", "ratio": 150, "first": { "pane": 0 }, "second": { "pane": 1 } } },
+        { "split": { "direction": "horizontal", "ratio": 50, "first": { "pane": 0 }, "second": {
+            "split": { "direction": "vertical", "ratio": 0, "first": { "pane": 1 }, "second": { "pane": 2 } }
+        } } }
+    ]);
+    fs::write(config_path, config.to_string()).expect("Failed to write file");
+
+    let app_config = AppConfig::load(config_path);
+    fs::remove_file(config_path).expect("Failed to delete file");
+    let app_config = app_config.expect("Failed to load config");
+    let preset = app_config.get_preset_by_name("Layout").unwrap();
+
+    assert_eq!(
+        preset.validate(),
+        vec![
+            String::from("Tab (#1) layout split ratios must be between 10 and 90."),
+            String::from("Tab (#2) layout split ratios must be between 10 and 90.")
+        ]
+    );
+    // Launching and drawing fall back to the default layouts.
+    assert_eq!(preset.layout(0), PaneLayout::default_for(2));
+    assert_eq!(preset.layout(1), PaneLayout::default_for(3));
+    assert!(app_config.create_launch_plan("Layout").is_err());
+}
+
+#[test]
+fn keymap_parses_and_resolves_keys() {
+    use crossterm::event::{ KeyCode, KeyModifiers };
+
+    let key = |text: &str| text.parse::<KeyPress>().unwrap();
+    let resolve = |keymap: &Keymap, pending: &mut Vec<KeyPress>, text: &str| {
+        keymap.resolve(KeyContext::List, pending, key(text))
+    };
+
+    assert_eq!(key("ctrl+c"), KeyPress::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    assert_eq!(key("shift+g"), KeyPress::new(KeyCode::Char('G'), KeyModifiers::SHIFT));
+    assert_eq!(key("G").to_string(), "G");
+    assert_eq!(key("delete").to_string(), "DEL");
+    assert!("ctrl+nope".parse::<KeyPress>().is_err());
+
+    let keymap = Keymap::default();
+    let mut pending = vec![];
+    assert_eq!(resolve(&keymap, &mut pending, "q"), Some(Action::Quit));
+    assert_eq!(resolve(&keymap, &mut pending, "j"), None);
+    assert_eq!(keymap.label(Action::Back), Some(String::from("ESC")));
+
+    let vim = Keymap::new(
+        &serde_json::from_str::<KeymapSettings>("{\"preset\":\"vim\"}").unwrap()
+    ).unwrap();
+    assert_eq!(resolve(&vim, &mut pending, "j"), Some(Action::Down));
+    assert_eq!(resolve(&vim, &mut pending, "g"), None);
+    assert_eq!(resolve(&vim, &mut pending, "g"), Some(Action::First));
+    assert_eq!(resolve(&vim, &mut pending, "g"), None);
+    assert_eq!(resolve(&vim, &mut pending, "k"), Some(Action::Up));
+    assert!(pending.is_empty());
+    assert_eq!(resolve(&vim, &mut pending, "shift+g"), Some(Action::Last));
+}
+
+#[test]
+fn keymap_overrides_and_conflicts() {
+    let settings = |json: &str| serde_json::from_str::<KeymapSettings>(json).unwrap();
+
+    let keymap = Keymap::new(
+        &settings("{\"bindings\":{\"quit\":[\"ctrl+q\"],\"edit\":[]}}")
+    ).unwrap();
+    assert_eq!(keymap.label(Action::Quit), Some(String::from("CTRL+q")));
+    assert_eq!(keymap.label(Action::Edit), None);
+
+    let same_key = Keymap::new(&settings("{\"bindings\":{\"quit\":[\"e\"]}}"));
+    assert!(same_key.unwrap_err().contains("'e' is bound to both quit and edit."));
+
+    let prefix = Keymap::new(&settings("{\"preset\":\"vim\",\"bindings\":{\"edit\":[\"g\"]}}"));
+    assert!(prefix.unwrap_err().contains("start with the same keys"));
+
+    let text_key = Keymap::new(&settings("{\"bindings\":{\"copy\":[\"c\"]}}"));
+    assert!(text_key.unwrap_err().contains("types text"));
+
+    // The same key may be used by list and text actions.
+    assert!(Keymap::new(&settings("{\"bindings\":{\"launch\":[\"tab\"],\"submit\":[\"tab\"]}}")).is_ok());
+}
+
+#[test]
+fn themes_resolve_from_settings() {
+    use tui::style::{ Color, Modifier };
+
+    let settings = |json: &str| serde_json::from_str::<ThemeSettings>(json).unwrap();
+
+    assert_eq!(theme::parse_color("#3399ff"), Ok(Color::Rgb(51, 153, 255)));
+    assert_eq!(theme::parse_color("Light-Blue"), Ok(Color::LightBlue));
+    assert_eq!(theme::parse_color("208"), Ok(Color::Indexed(208)));
+    assert!(theme::parse_color("#12345").is_err());
+    assert!(theme::parse_color("blurple").is_err());
+
+    assert_eq!(Theme::new(&settings("{}"), false), Ok(Theme::dark()));
+    assert_eq!(Theme::new(&settings("{\"name\":\"light\"}"), false), Ok(Theme::light()));
+    assert!(Theme::new(&settings("{\"name\":\"solarized\"}"), false).is_err());
+
+    let custom = settings(
+        "{\"name\":\"mine\",\"custom\":{\"mine\":{\"base\":\"light\",\"error\":\"#ff0000\"}}}"
+    );
+    let theme = Theme::new(&custom, false).unwrap();
+    assert_eq!(theme.popup(PopupKind::Error).fg, Some(Color::Rgb(255, 0, 0)));
+    assert_eq!(theme.text(), Theme::light().text());
+
+    let broken = settings("{\"name\":\"mine\",\"custom\":{\"mine\":{\"text\":\"nope\"}}}");
+    assert!(Theme::new(&broken, false).is_err());
+
+    let no_color = Theme::new(&custom, true).unwrap();
+    assert_eq!(no_color.popup(PopupKind::Error).fg, Some(Color::Reset));
+    assert!(no_color.highlight().add_modifier.contains(Modifier::REVERSED));
+}
+
+#[test]
+fn cli_no_subcommand_starts_tui() {
+    let cli = Cli::try_parse_from(["workspace_opener"]).unwrap();
+
+    assert_eq!(cli.command, None);
+    assert_eq!(cli.global.config, "config.json");
+}
+
+#[test]
+fn cli_json_output() {
+    let config_path = "test_cli_json.json";
+    let mut app_config = AppConfig::default();
+    let mut broken = Preset::default();
+    broken
+        .change_field_value(PresetValue::Name("Broken".to_string()))
+        .expect("Failed changing preset's name");
+    broken
+        .change_field_value(PresetValue::PresetInfo(PresetInfoValue::InitShell(ShellType::Bash)))
+        .expect("Failed changing preset's init shell");
+    app_config.add_presets(vec![Preset::default(), broken]);
+    app_config.write_preset_to_file(&[], WriteType::Edit, config_path).expect("Failed to write file");
+
+    let run = |args: &[&str]| -> (Result<(), String>, serde_json::Value) {
+        let cli = Cli::try_parse_from(
+            ["workspace_opener", "--config", config_path, "--format", "json"]
+                .iter()
+                .chain(args.iter())
+        ).unwrap();
+        let mut out = Vec::new();
+        let result = cli::execute(cli.command.unwrap(), &cli.global, &mut out);
+        (result, serde_json::from_slice(&out).unwrap())
+    };
+
+    let (list_result, list) = run(&["list"]);
+    let (show_result, show) = run(&["show", "Test Preset"]);
+    let (plan_result, plan) = run(&["run", "Test Preset", "--dry-run"]);
+    let (broken_result, broken_plan) = run(&["run", "Broken", "--dry-run"]);
+
+    fs::remove_file(config_path).expect("Failed to delete file");
+
+    assert!(list_result.is_ok() && show_result.is_ok() && plan_result.is_ok());
+    assert!(broken_result.is_err());
+
+    assert_eq!(list["presets"][0]["name"], "Test Preset");
+    assert_eq!(list["presets"][0]["problems"], serde_json::json!([]));
+    assert_eq!(list["presets"][1]["problems"].as_array().unwrap().len(), 1);
+
+    assert_eq!(show["windows"], serde_json::json!([2, 1, 1]));
+    assert_eq!(show["preset_info"]["target_shell"], "powershell");
+
+    assert_eq!(plan["plan"]["backend"], "wt");
+    assert_eq!(plan["plan"]["program"], "powershell.exe");
+    assert_eq!(
+        plan["plan"]["args"][0],
+        "wt.exe powershell -NoExit -Command 'arg w1\\;'`; sp powershell -NoExit -Command 'arg w1\\;'`; nt powershell -NoExit -Command 'arg w2\\;'`; nt powershell -NoExit -Command 'arg w3\\;'"
+    );
+
+    assert_eq!(broken_plan["plan"], serde_json::Value::Null);
+    assert_eq!(broken_plan["problems"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn cli_show_reports_problems_of_a_broken_preset() {
+    let config_path = "test_cli_show_broken.json";
+    let mut app_config = AppConfig::default();
+    app_config.add_presets(vec![Preset::default()]);
+    app_config.write_preset_to_file(&[], WriteType::Edit, config_path).expect("Failed to write file");
+
+    // Args without any windows, as left by a hand edited config.
+    let json = fs::read_to_string(config_path).expect("Failed to read file");
+    let mut config: serde_json::Value = serde_json::from_str(&json).unwrap();
+    config["presets"][0]["windows"] = serde_json::json!([]);
+    fs::write(config_path, config.to_string()).expect("Failed to write file");
+
+    let cli = Cli::try_parse_from(["workspace_opener", "--config", config_path, "show", "Test Preset"]).unwrap();
+    let mut out = Vec::new();
+    let result = cli::execute(cli.command.unwrap(), &cli.global, &mut out);
+
+    fs::remove_file(config_path).expect("Failed to delete file");
+
+    let show = String::from_utf8(out).unwrap();
+    assert!(result.is_ok());
+    assert!(show.lines().all(|line| line.starts_with("Problem: ")));
+    assert!(show.contains("Problem: Expected windows for 3 tabs, found 0.\n"));
+}
+
+#[test]
+fn cli_set_refuses_a_broken_preset() {
+    let config_path = "test_cli_set_broken.json";
+    let mut app_config = AppConfig::default();
+    app_config.add_presets(vec![Preset::default()]);
+    app_config.write_preset_to_file(&[], WriteType::Edit, config_path).expect("Failed to write file");
+
+    let json = fs::read_to_string(config_path).expect("Failed to read file");
+    let mut config: serde_json::Value = serde_json::from_str(&json).unwrap();
+    config["presets"][0]["windows"] = serde_json::json!([]);
+    let broken = config.to_string();
+    fs::write(config_path, &broken).expect("Failed to write file");
+
+    let mut results = vec![];
+    for (field, value) in [("tabs", "2"), ("name", "Renamed")] {
+        let cli = Cli::try_parse_from(
+            ["workspace_opener", "--config", config_path, "set", "Test Preset", field, value]
+        ).unwrap();
+        results.push(cli::execute(cli.command.unwrap(), &cli.global, &mut Vec::new()));
+    }
+    let after = fs::read_to_string(config_path).expect("Failed to read file");
+
+    fs::remove_file(config_path).expect("Failed to delete file");
+
+    for result in results {
+        assert!(result.unwrap_err().starts_with("Cannot edit preset: Expected windows for 3 tabs, found 0."));
+    }
+    assert_eq!(after, broken);
+}
+
+#[test]
+fn completion_scripts_cover_subcommands() {
+    let subcommands = Cli::command()
+        .get_subcommands()
+        .map(|sc| sc.get_name().to_string())
+        .collect::<Vec<String>>();
+
+    for shell in [
+        CompletionShell::Bash,
+        CompletionShell::Zsh,
+        CompletionShell::Fish,
+        CompletionShell::Powershell,
+    ] {
+        let cli = Cli::try_parse_from(
+            ["workspace_opener", "--config", "missing.json", "completions"]
+                .iter()
+                .chain([shell.to_possible_value().unwrap().get_name()].iter())
+        ).unwrap();
+        let mut out = Vec::new();
+        cli::execute(cli.command.unwrap(), &cli.global, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+
+        assert!(script.contains("list"), "{:?} script does not complete presets", shell);
+        for subcommand in &subcommands {
+            assert!(script.contains(subcommand.as_str()), "{:?} misses {}", shell, subcommand);
+        }
+    }
+}
+
+#[test]
+fn doctor_parses_wt_profiles_with_comments() {
+    let settings =
+        r#"{
+        // This file was initially generated by Windows Terminal
+        "defaultProfile": "{61c54bbd}",
+        "profiles": {
+            "defaults": {},
+            "list": [
+                { "name": "Windows PowerShell", "commandline": "powershell.exe" },
+                /* hidden */ { "name": "Ubuntu", "source": "Windows.Terminal.Wsl" },
+                { "name": "Odd // name", "hidden": false }
+            ]
+        }
+    }"#;
+
+    assert_eq!(
+        doctor::parse_wt_profiles(settings),
+        vec!["Windows PowerShell", "Ubuntu", "Odd // name"]
+    );
+}
+
+#[test]
+fn doctor_finds_cd_targets() {
+    assert_eq!(doctor::cd_targets("ls, cd Rust` Projects, pwd"), vec!["Rust Projects"]);
+    assert_eq!(doctor::cd_targets("cd \"C:\\Users\\me\",npm run dev"), vec!["C:\\Users\\me"]);
+    assert!(doctor::cd_targets("ls, pwd").is_empty());
+}
+
+#[test]
+fn doctor_reports_broken_config() {
+    let config_path = "test_doctor.json";
+    fs::write(config_path, "{\"presets\":[").expect("Failed to write file");
+
+    let checks = doctor::run_checks(config_path, "test_doctor.log");
+
+    fs::remove_file(config_path).expect("Failed to delete file");
+    fs::remove_file("test_doctor.log").expect("Failed to delete file");
+
+    assert_eq!(checks[0].get_status(), CheckStatus::Fail);
+    assert!(checks[0].to_lines()[0].starts_with("[FAIL] Config:"));
+    assert_eq!(checks.last().unwrap().get_status(), CheckStatus::Pass);
+}
+
+#[test]
+fn log_options_resolve() {
+    let settings: Settings = serde_json
+        ::from_str("{\"debug_mode\":true,\"log\":{\"level\":\"warn\"}}")
+        .expect("Failed to parse settings");
+
+    let options = LogOptions::resolve(&settings, "configs/config.json", None, None);
+    assert_eq!(options.get_path(), Path::new("configs/state/output.log"));
+    assert_eq!(options.get_level(), LevelFilter::Debug);
+
+    let options = LogOptions::resolve(
+        &settings,
+        "config.json",
+        Some("custom.log"),
+        Some(LogLevel::Error)
+    );
+    assert_eq!(options.get_path(), Path::new("custom.log"));
+    assert_eq!(options.get_level(), LevelFilter::Error);
+
+    let old_settings: Settings = serde_json
+        ::from_str("{\"debug_mode\":false}")
+        .expect("Failed to parse settings");
+    let options = LogOptions::resolve(&old_settings, "config.json", None, None);
+    assert_eq!(options.get_level(), LevelFilter::Info);
+}
+
+#[test]
+fn crash_report_is_written_to_the_state_dir() {
+    let report = crash::crash_report(
+        "panicked at src/main.rs:1:1:\nboom",
+        Some("State: EditPreset\nInputMode: Edit"),
+        "0: main"
+    );
+    assert!(report.contains("State: EditPreset\nInputMode: Edit\n\npanicked at src/main.rs:1:1:\nboom"));
+    assert!(report.ends_with("Backtrace:\n0: main"));
+    assert!(!crash::crash_report("boom", None, "").contains("State:"));
+
+    let state_dir = std::env::temp_dir().join("workspace_opener_crash_test").join("state");
+    let path = crash::write_crash_report(&state_dir, &report).unwrap();
+    assert!(path.starts_with(&state_dir));
+    assert!(path.file_name().unwrap().to_string_lossy().starts_with("crash-"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), report);
+    fs::remove_dir_all(state_dir.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn config_watcher_reports_changes() {
+    let config_path = std::env::temp_dir().join("workspace_opener_watch_test.json");
+    fs::write(&config_path, "{}").unwrap();
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    events::watch_config(config_path.clone(), sender, std::time::Duration::from_millis(10));
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert!(receiver.try_recv().is_err());
+
+    fs::write(&config_path, "{\"presets\":[]}").unwrap();
+    let event = receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+    assert!(matches!(event, AppEvent::Message(AppMessage::ConfigChanged)));
+    fs::remove_file(config_path).unwrap();
+}
+
+#[test]
+fn log_file_rotates_by_size() {
+    let dir = Path::new("test_log_rotation");
+    let _ = fs::remove_dir_all(dir);
+    let path = dir.join("output.log");
+
+    {
+        let mut file = RotatingFile::open(&path, 10, 2).expect("Failed to open log");
+        file.write_all(b"first\n").unwrap();
+        file.write_all(b"second\n").unwrap();
+        file.write_all(b"third\n").unwrap();
+        file.write_all(b"fourth\n").unwrap();
+    }
+    {
+        let mut file = RotatingFile::open(&path, 10, 2).expect("Failed to reopen log");
+        file.write_all(b"fifth\n").unwrap();
+    }
+
+    let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap_or_default();
+    let (current, first, second, third) = (
+        read("output.log"),
+        read("output.log.1"),
+        read("output.log.2"),
+        read("output.log.3"),
+    );
+
+    fs::remove_dir_all(dir).expect("Failed to delete directory");
+
+    assert_eq!(current, "fifth\n");
+    assert_eq!(first, "fourth\n");
+    assert_eq!(second, "third\n");
+    assert_eq!(third, "");
+}
+
+#[test]
+fn help_lists_actions_for_state_and_input_mode() {
+    let keymap = Keymap::default();
+    let find = |sections: &[HelpSection], title: &str| {
+        sections
+            .iter()
+            .find(|section| section.get_title() == title)
+            .map(|section| section.get_entries().to_vec())
+    };
+
+    let choose = help::help_sections(&keymap, State::ChoosePreset, &InputMode::Normal);
+    let presets = find(&choose, "Presets").unwrap();
+    assert_eq!(presets[0], (String::from("e"), String::from("edit the selected preset")));
+    assert!(find(&choose, "Navigation").unwrap().contains(
+        &(String::from("ENTER"), String::from("run the selected preset"))
+    ));
+    assert_eq!(find(&choose, "General").unwrap()[0].0, "? / F1");
+
+    let start = help::help_sections(&keymap, State::Start, &InputMode::Normal);
+    assert!(find(&start, "Presets").is_none());
+
+    // Keys that type text are not listed while typing.
+    let filter = help::help_sections(&keymap, State::ChoosePreset, &InputMode::Filter);
+    assert_eq!(find(&filter, "General").unwrap()[0].0, "F1");
+    assert!(find(&filter, "Selection").unwrap().contains(
+        &(String::from("CTRL+c"), String::from("copy the selection"))
+    ));
+    assert!(find(&filter, "Editing").unwrap().contains(
+        &(String::from("CTRL+BACKSPACE / CTRL+w"), String::from("delete the previous word"))
+    ));
+    assert!(find(&filter, "Input").unwrap().contains(
+        &(String::from("ESC"), String::from("close the filter"))
+    ));
+
+    let custom = Keymap::new(
+        &serde_json::from_str::<KeymapSettings>("{\"bindings\":{\"quit\":[],\"edit\":[\"f2\"]}}").unwrap()
+    ).unwrap();
+    let choose = help::help_sections(&custom, State::ChoosePreset, &InputMode::Normal);
+    assert_eq!(find(&choose, "Presets").unwrap()[0].0, "F2");
+    assert!(!find(&choose, "General").unwrap().iter().any(|(_, description)| description == "exit"));
+}
+
+#[test]
+fn text_counts_graphemes_and_columns() {
+    let input = "a東e\u{301}👍b";
+
+    assert_eq!(text::grapheme_count(input), 5);
+    assert_eq!(text::graphemes(input)[2], "e\u{301}");
+    assert_eq!(text::byte_offset(input, 3), 1 + 3 + 3);
+    assert_eq!(text::byte_offset(input, 10), input.len());
+    assert_eq!(&input[text::byte_range(input, 1, 3)], "東e\u{301}");
+
+    assert_eq!(text::display_width(input, 2), 3);
+    assert_eq!(text::display_width(input, 5), 7);
+
+    assert_eq!(text::index_at_column(input, 0), 0);
+    assert_eq!(text::index_at_column(input, 1), 1);
+    // The right half of a wide character.
+    assert_eq!(text::index_at_column(input, 2), 1);
+    assert_eq!(text::index_at_column(input, 3), 2);
+    assert_eq!(text::index_at_column(input, 6), 4);
+    assert_eq!(text::index_at_column(input, 40), 5);
+}
+
+fn typed_editor(text: &str) -> LineEditor {
+    let mut editor = LineEditor::default();
+    for ch in text.chars() {
+        editor.insert_char(ch);
+    }
+    editor
+}
+
+#[test]
+fn line_editor_home_end_and_delete_forward() {
+    let mut editor = typed_editor("hello");
+    editor.home(false);
+    assert_eq!(editor.cursor(), 0);
+    editor.delete_forward();
+    assert_eq!(editor.text(), "ello");
+
+    editor.end(false);
+    editor.delete_forward();
+    assert_eq!(editor.text(), "ello");
+    assert_eq!(editor.cursor(), 4);
+
+    editor.home(true);
+    assert_eq!(editor.selected_text(), Some("ello"));
+}
+
+#[test]
+fn line_editor_select_all_cut_and_paste_over_selection() {
+    let mut editor = typed_editor("one two");
+    editor.select_all();
+    assert_eq!(editor.cut(), Some(String::from("one two")));
+    assert_eq!(editor.text(), "");
+    assert_eq!(editor.cut(), None);
+
+    let mut editor = typed_editor("one two");
+    editor.move_word_left(true);
+    editor.insert_str("three");
+    assert_eq!(editor.text(), "one three");
+    assert_eq!(editor.cursor(), 9);
+    assert_eq!(editor.selection(), None);
+
+    editor.home(false);
+    editor.move_right(true);
+    editor.move_right(true);
+    editor.move_left(true);
+    assert_eq!(editor.selection(), Some(0..1));
+    editor.insert_str("ø");
+    assert_eq!(editor.text(), "øne three");
+    assert_eq!(editor.cursor(), 1);
+}
+
+#[test]
+fn line_editor_word_deletion() {
+    let mut editor = typed_editor("cd ~/projects && ls");
+    editor.delete_word_left();
+    assert_eq!(editor.text(), "cd ~/projects && ");
+
+    editor.home(false);
+    editor.delete_word_right();
+    assert_eq!(editor.text(), "~/projects && ");
+
+    editor.move_word_right(false);
+    editor.delete_word_left();
+    assert_eq!(editor.text(), "&& ");
+}
+
+#[test]
+fn line_editor_undo_and_redo() {
+    let mut editor = typed_editor("cargo build");
+    editor.delete_word_left();
+    assert_eq!(editor.text(), "cargo ");
+
+    editor.undo();
+    assert_eq!(editor.text(), "cargo build");
+    // Typed words are undone one at a time.
+    editor.undo();
+    assert_eq!(editor.text(), "cargo");
+    editor.undo();
+    assert_eq!(editor.text(), "");
+    editor.undo();
+    assert_eq!(editor.text(), "");
+
+    editor.redo();
+    editor.redo();
+    assert_eq!(editor.text(), "cargo build");
+    assert_eq!(editor.cursor(), 11);
+
+    editor.undo();
+    editor.insert_char('!');
+    editor.redo();
+    assert_eq!(editor.text(), "cargo!");
+}
+
+#[test]
+fn line_editor_scrolls_to_keep_the_cursor_visible() {
+    let mut editor = typed_editor("abcdefghij");
+    assert_eq!(editor.scroll_to_cursor(5), 6..10);
+    assert_eq!(editor.cursor_column(), 4);
+
+    editor.home(false);
+    assert_eq!(editor.scroll_to_cursor(5), 0..5);
+    assert_eq!(editor.cursor_column(), 0);
+
+    // Moving back inside the visible part does not scroll.
+    editor.end(false);
+    editor.scroll_to_cursor(5);
+    editor.move_left(false);
+    editor.move_left(false);
+    assert_eq!(editor.scroll_to_cursor(5), 6..10);
+    editor.click(1);
+    assert_eq!(editor.cursor(), 7);
+
+    let mut wide = typed_editor("東京都");
+    assert_eq!(wide.scroll_to_cursor(4), 2..3);
+    assert_eq!(wide.cursor_column(), 2);
+}
+
+#[test]
+fn line_editor_edits_non_ascii_text() {
+    let mut editor = typed_editor("C:\\Users\\Jürgen");
+    assert_eq!(editor.cursor(), 15);
+
+    editor.backspace();
+    editor.move_word_left(false);
+    assert_eq!(editor.cursor(), 0);
+    editor.move_word_right(false);
+    assert_eq!(editor.cursor(), 14);
+    assert_eq!(editor.text(), "C:\\Users\\Jürge");
+
+    // CJK and emoji take two columns each.
+    let mut editor = typed_editor("東京 👍");
+    assert_eq!(editor.cursor(), 4);
+    assert_eq!(text::display_width(editor.text(), editor.cursor()), 7);
+
+    editor.move_left(false);
+    editor.move_left(true);
+    editor.move_left(true);
+    assert_eq!(editor.selection(), Some(1..3));
+    editor.insert_char('都');
+    assert_eq!(editor.text(), "東都👍");
+    assert_eq!(editor.cursor(), 2);
+
+    editor.move_right(false);
+    editor.backspace();
+    assert_eq!(editor.text(), "東都");
+}
+
+#[test]
+fn line_editor_keeps_combining_characters_together() {
+    // `e` followed by a combining acute accent is one grapheme.
+    let mut editor = typed_editor("cafe\u{301}s");
+    assert_eq!(editor.cursor(), 5);
+    assert_eq!(text::display_width(editor.text(), editor.cursor()), 5);
+
+    editor.move_left(false);
+    editor.move_left(false);
+    assert_eq!(editor.cursor(), 3);
+    editor.move_right(true);
+    assert_eq!(editor.selection(), Some(3..4));
+    editor.backspace();
+    assert_eq!(editor.text(), "cafs");
+
+    // A flag is two code points and a family emoji is joined by zero width joiners.
+    let mut editor = typed_editor("🇵🇱👨\u{200d}👩\u{200d}👧");
+    assert_eq!(editor.cursor(), 2);
+    editor.backspace();
+    assert_eq!(editor.text(), "🇵🇱");
+    editor.backspace();
+    assert!(editor.text().is_empty());
+}
+
+#[test]
+fn messages_close_on_their_own_but_errors_stay() {
+    let before = Instant::now();
+    let mut popup = Popup::default();
+    popup.activate_popup("Saved.", PopupKind::Success);
+    let after = Instant::now();
+    assert!(!popup.is_expired(before + Duration::from_secs(1)));
+    assert!(popup.is_expired(after + Duration::from_secs(4)));
+
+    popup.activate_popup("Broken.", PopupKind::Error);
+    assert!(!popup.is_expired(Instant::now() + Duration::from_secs(40)));
+    popup.deactivate_popup();
+    assert!(!popup.is_expired(Instant::now() + Duration::from_secs(40)));
+}
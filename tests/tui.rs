@@ -0,0 +1,680 @@
+use crossterm::event::{ KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind };
+use std::{
+    collections::VecDeque,
+    fs,
+    path::PathBuf,
+    sync::{ mpsc::{ self, Sender }, Arc, Mutex },
+    thread,
+    time::{ Duration, Instant },
+};
+use tui::{ backend::TestBackend, Terminal };
+use workspace_opener::workspace_opener_lib::api::{ run_app, Runner };
+use workspace_opener::workspace_opener_lib::app::App;
+use workspace_opener::workspace_opener_lib::events::{
+    self,
+    AppEvent,
+    AppMessage,
+    EventSource,
+    ScriptedEvents,
+};
+use workspace_opener::workspace_opener_lib::model::{
+    AppConfig,
+    LaunchPlan,
+    Preset,
+    PresetInfo,
+    ShellType,
+    WriteType,
+};
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 24;
+
+/// A config file of its own for each test, as the tests run in parallel.
+struct Harness {
+    config_path: PathBuf,
+    plans: Arc<Mutex<Vec<LaunchPlan>>>,
+}
+
+impl Harness {
+    fn new(name: &str, presets: Vec<Preset>) -> Harness {
+        let dir = std::env::temp_dir().join(format!("workspace_opener_tui_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.json");
+
+        let mut app_config = AppConfig::default();
+        app_config.add_presets(presets);
+        app_config
+            .write_preset_to_file(&[], WriteType::Edit, &config_path.to_string_lossy())
+            .unwrap();
+
+        Harness { config_path, plans: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// A launcher stub that records the plans instead of starting wt.
+    fn runner(&self) -> Runner {
+        let plans = self.plans.clone();
+        Arc::new(move |plan: &LaunchPlan| {
+            plans.lock().unwrap().push(plan.clone());
+            Ok(())
+        })
+    }
+
+    /// Plays `script` and returns the last screen drawn.
+    fn run(&self, script: Vec<AppEvent>) -> String {
+        self.run_with(script, self.runner())
+    }
+
+    fn run_with(&self, script: Vec<AppEvent>, runner: Runner) -> String {
+        self.play(&mut ScriptedEvents::new(script), runner)
+    }
+
+    /// Plays `steps`, running their changes on the way.
+    fn run_steps(&self, steps: Vec<Step>) -> String {
+        let mut events = Steps { steps: steps.into(), posted: ScriptedEvents::new(vec![]) };
+        self.play(&mut events, self.runner())
+    }
+
+    fn play(&self, events: &mut impl EventSource, runner: Runner) -> String {
+        let config_path = self.config_path.to_string_lossy();
+        let log_path = self.config_path.with_file_name("output.log");
+        let mut app = App::new(&config_path, &log_path.to_string_lossy());
+
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        run_app(&mut terminal, &mut app, events, runner).unwrap();
+
+        snapshot(&terminal)
+    }
+
+    /// Changes one setting in the config file, e.g. `close_after_launch`.
+    fn set_setting(&self, name: &str, value: serde_json::Value) {
+        let json = fs::read_to_string(&self.config_path).unwrap();
+        let mut config: serde_json::Value = serde_json::from_str(&json).unwrap();
+        config["settings"][name] = value;
+        fs::write(&self.config_path, config.to_string()).unwrap();
+    }
+
+    fn setting(&self, name: &str) -> serde_json::Value {
+        let json = fs::read_to_string(&self.config_path).unwrap();
+        let config: serde_json::Value = serde_json::from_str(&json).unwrap();
+        config["settings"][name].clone()
+    }
+
+    fn config(&self) -> AppConfig {
+        AppConfig::load(&self.config_path.to_string_lossy()).unwrap()
+    }
+
+    fn launched(&self) -> Vec<LaunchPlan> {
+        self.plans.lock().unwrap().clone()
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        if let Some(dir) = self.config_path.parent() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// A scripted event, or a change made while the app runs.
+enum Step {
+    Event(AppEvent),
+    Do(Box<dyn FnOnce()>),
+}
+
+/// Plays steps, taking the messages posted by the app first like `ScriptedEvents`.
+struct Steps {
+    steps: VecDeque<Step>,
+    posted: ScriptedEvents,
+}
+
+impl EventSource for Steps {
+    fn next(&mut self) -> Option<AppEvent> {
+        if let Some(message) = self.posted.next() {
+            return Some(message);
+        }
+        loop {
+            match self.steps.pop_front()? {
+                Step::Event(event) => {
+                    return Some(event);
+                }
+                Step::Do(change) => change(),
+            }
+        }
+    }
+
+    fn sender(&self) -> Sender<AppEvent> {
+        self.posted.sender()
+    }
+}
+
+/// The rendered buffer as text, one line per row without trailing spaces.
+fn snapshot(terminal: &Terminal<TestBackend>) -> String {
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol.as_str())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Row `index` of a snapshot.
+fn row(screen: &str, index: usize) -> &str {
+    screen.lines().nth(index).unwrap_or_default()
+}
+
+/// The text of the input box at the bottom.
+fn input_text(screen: &str) -> &str {
+    row(screen, (HEIGHT as usize) - 2)
+        .trim_matches('│')
+        .trim_end()
+}
+
+fn key(code: KeyCode) -> AppEvent {
+    events::key(code)
+}
+
+fn ctrl(ch: char) -> AppEvent {
+    events::key_with(KeyCode::Char(ch), KeyModifiers::CONTROL)
+}
+
+fn click(column: u16, row: u16) -> AppEvent {
+    AppEvent::Mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+fn preset(name: &str) -> Preset {
+    let input = [name, "1", "1", "ls"];
+    Preset::from_input(&input.map(String::from))
+}
+
+fn enter() -> AppEvent {
+    key(KeyCode::Enter)
+}
+
+/// Replaces the text of the input box with each answer and submits it.
+fn answers(texts: &[&str]) -> Vec<AppEvent> {
+    let mut script = vec![];
+    for text in texts {
+        script.push(ctrl('a'));
+        script.extend(events::typed(text));
+        script.push(enter());
+    }
+    script
+}
+
+/// Start screen to the preset wizard, then answers every question.
+fn create_script(answers: &[&str]) -> Vec<AppEvent> {
+    let mut script = vec![key(KeyCode::Down), enter()];
+    for answer in answers {
+        script.extend(events::typed(answer));
+        script.push(enter());
+    }
+    script
+}
+
+#[test]
+fn create_flow_reviews_and_writes_the_preset() {
+    let harness = Harness::new("create", vec![]);
+    // The wt profile and shells are prefilled with the defaults.
+    let mut script = create_script(&["Work", "1", "2", "ls", "cargo test"]);
+    script.extend([enter(), enter(), enter()]);
+
+    let review = harness.run(script.clone());
+    assert_eq!(review, REVIEW_SNAPSHOT);
+    assert!(harness.config().get_preset_by_name("Work").is_none());
+
+    script.push(enter());
+    let screen = harness.run(script);
+    assert!(screen.contains("Preset created successfuly :)"));
+
+    let config = harness.config();
+    let preset = config.get_preset_by_name("Work").expect("The preset was not written.");
+    assert_eq!(preset.get_tab_args(0), Some(&["ls".to_string(), "cargo test".to_string()][..]));
+    assert!(harness.launched().is_empty());
+}
+
+#[test]
+fn quit_key_keeps_unsaved_layouts_and_fields() {
+    let input = ["Home", "1", "2", "ls", "pwd"];
+    let harness = Harness::new("quit", vec![Preset::from_input(&input.map(String::from))]);
+    let mut script = vec![enter(), key(KeyCode::Char('e')), key(KeyCode::Down), key(KeyCode::Down)];
+    script.extend([key(KeyCode::Char('l')), key(KeyCode::Char('v')), key(KeyCode::Char('q'))]);
+
+    // The layout editor and the dropdown stay open, ENTER saves after all.
+    script.push(enter());
+    let screen = harness.run(script);
+    assert!(!screen.contains("Layout of tab #1"));
+    let json = fs::read_to_string(&harness.config_path).unwrap();
+    let config: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(config["presets"][0]["layouts"][0]["split"]["first"]["split"].is_object());
+
+    let mut script = vec![key(KeyCode::Down), key(KeyCode::Down), enter()];
+    script.extend([key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Down), enter()]);
+    script.extend([key(KeyCode::Down), key(KeyCode::Char('q')), enter()]);
+    harness.run(script);
+    assert_eq!(harness.setting("preset_defaults")["init_shell"], "pwsh");
+}
+
+#[test]
+fn broken_config_is_reported_instead_of_crashing() {
+    let harness = Harness::new("broken_config", vec![]);
+    fs::write(&harness.config_path, "{ \"presets\": [").unwrap();
+
+    let config_path = harness.config_path.to_string_lossy();
+    let mut app = App::new(&config_path, "");
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    let result = run_app(&mut terminal, &mut app, &mut ScriptedEvents::new(vec![enter()]), harness.runner());
+    assert!(result.unwrap_err().contains(config_path.as_ref()));
+}
+
+#[test]
+fn failed_saves_show_an_error() {
+    // A folder in place of the config file cannot be written.
+    let block = |harness: &Harness| {
+        let config_path = harness.config_path.clone();
+        Step::Do(
+            Box::new(move || {
+                fs::remove_file(&config_path).unwrap();
+                fs::create_dir(&config_path).unwrap();
+            })
+        )
+    };
+
+    let harness = Harness::new("failed_create", vec![]);
+    let mut steps = create_script(&["Work", "1", "1", "ls"]).into_iter().map(Step::Event).collect::<Vec<Step>>();
+    steps.push(block(&harness));
+    steps.extend([enter(), enter(), enter(), enter()].map(Step::Event));
+
+    let screen = harness.run_steps(steps);
+    assert!(screen.contains("│Cannot write the config file"));
+    assert!(row(&screen, 1).starts_with("┌Review"));
+
+    let harness = Harness::new("failed_delete", vec![preset("Home")]);
+    let steps = vec![
+        Step::Event(enter()),
+        block(&harness),
+        Step::Event(key(KeyCode::Delete)),
+        Step::Event(key(KeyCode::Char('y')))
+    ];
+
+    let screen = harness.run_steps(steps);
+    assert!(screen.contains("│Cannot write the config file"));
+}
+
+#[test]
+fn edit_flow_renames_the_preset() {
+    let harness = Harness::new("edit", vec![preset("Home")]);
+    let mut script = vec![enter(), key(KeyCode::Char('e')), enter(), ctrl('a')];
+    script.extend(events::typed("Office"));
+    script.push(enter());
+
+    let screen = harness.run(script);
+    assert!(screen.contains("│> Name: Office"));
+
+    let config = harness.config();
+    assert!(config.get_preset_by_name("Home").is_none());
+    let preset = config.get_preset_by_name("Office").expect("The preset was not renamed.");
+    assert_eq!(preset.get_tab_args(0), Some(&["ls".to_string()][..]));
+}
+
+#[test]
+fn edit_flow_lists_a_preset_with_more_args_than_windows() {
+    let broken = Preset::new(String::from("Broken"), 1, vec![], vec![String::from("ls")], PresetInfo::default());
+    let harness = Harness::new("edit_broken", vec![broken]);
+
+    let screen = harness.run(vec![enter(), key(KeyCode::Char('e'))]);
+    assert!(screen.contains("│> Name: Broken"));
+    assert!(screen.contains("│  Tabs: 1"));
+    assert!(!screen.contains("Arg"));
+}
+
+#[test]
+fn delete_flow_asks_then_moves_the_preset_to_the_trash() {
+    let harness = Harness::new("delete", vec![preset("Home")]);
+
+    let question = harness.run(vec![enter(), key(KeyCode::Delete)]);
+    assert_eq!(question, DELETE_SNAPSHOT);
+    assert!(harness.config().get_preset_by_name("Home").is_some());
+
+    let screen = harness.run(vec![enter(), key(KeyCode::Delete), key(KeyCode::Char('y'))]);
+    assert!(!screen.contains("Home"));
+
+    let config = harness.config();
+    assert!(config.get_preset_by_name("Home").is_none());
+    assert_eq!(config.get_trash().len(), 1);
+    assert_eq!(config.get_trash()[0].get_preset(), &preset("Home"));
+}
+
+#[test]
+fn launch_flow_runs_the_selected_preset_and_closes() {
+    let harness = Harness::new("launch", vec![preset("Home"), preset("Work")]);
+
+    // Keys after the launch are never read, the app has closed.
+    harness.run(vec![enter(), key(KeyCode::Down), enter(), key(KeyCode::Char('q'))]);
+
+    let expected = harness.config().create_launch_plan("Work").unwrap();
+    assert_eq!(harness.launched(), [expected]);
+}
+
+#[test]
+fn launch_flow_returns_the_error_of_a_failed_launch() {
+    let harness = Harness::new("launch_failed", vec![preset("Home")]);
+    let runner: Runner = Arc::new(|_: &LaunchPlan| Err(String::from("wt.exe was not found.")));
+
+    let config_path = harness.config_path.to_string_lossy();
+    let mut app = App::new(&config_path, "");
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    let result = run_app(&mut terminal, &mut app, &mut ScriptedEvents::new(vec![enter(), enter()]), runner);
+    assert_eq!(result, Err(String::from("wt.exe was not found.")));
+}
+
+#[test]
+fn launch_flow_stays_open_when_configured() {
+    let harness = Harness::new("launch_open", vec![preset("Home")]);
+    harness.set_setting("close_after_launch", serde_json::Value::Bool(false));
+
+    // The launch runs on its own thread, held until the screen is checked.
+    let record = harness.runner();
+    let (release, released) = mpsc::channel::<()>();
+    let released = Mutex::new(released);
+    let runner: Runner = Arc::new(move |plan: &LaunchPlan| {
+        let _ = released.lock().unwrap().recv();
+        record(plan)
+    });
+
+    let screen = harness.run_with(vec![enter(), enter()], runner);
+    assert!(screen.contains("Launching 'Home'..."));
+    assert!(harness.launched().is_empty());
+
+    release.send(()).unwrap();
+    let started = Instant::now();
+    while harness.launched().is_empty() && started.elapsed() < Duration::from_secs(5) {
+        thread::sleep(Duration::from_millis(10));
+    }
+    let expected = harness.config().create_launch_plan("Home").unwrap();
+    assert_eq!(harness.launched(), [expected]);
+}
+
+#[test]
+fn create_wizard_validates_steps_back_and_reviews() {
+    let harness = Harness::new("wizard", vec![preset("Home")]);
+    let mut script = vec![key(KeyCode::Down), enter()];
+
+    script.extend(answers(&["  "]));
+    assert!(harness.run(script.clone()).contains("│The name cannot be empty."));
+    script.extend(answers(&["Home"]));
+    assert!(harness.run(script.clone()).contains("already exists"));
+    script.extend(answers(&["Work", "11"]));
+    assert!(harness.run(script.clone()).contains("│Enter a number of tabs from 1 to 10."));
+    script.extend(answers(&["2", "5"]));
+    assert!(harness.run(script.clone()).contains("│Enter a number of windows from 1 to 4."));
+    script.extend(answers(&["2"]));
+    assert!(harness.run(script.clone()).contains("│Enter windows amount (1-4) for tab number 2:"));
+
+    // Going back restores the answer and drops the questions it added.
+    script.push(key(KeyCode::BackTab));
+    let screen = harness.run(script.clone());
+    assert_eq!(input_text(&screen), "2");
+    assert!(!screen.contains("tab number 2"));
+    script.push(key(KeyCode::BackTab));
+    let screen = harness.run(script.clone());
+    assert_eq!(input_text(&screen), "2");
+    assert!(row(&screen, 3).starts_with("│Work "));
+    assert!(!screen.contains("windows amount"));
+
+    script.extend(answers(&["1", "1", "ls", "", "powershell", "powershell"]));
+    let review = harness.run(script.clone());
+    assert!(row(&review, 1).starts_with("┌Review"));
+    assert!(review.contains("│  1. ls"));
+    assert!(harness.config().get_preset_by_name("Work").is_none());
+
+    // The review takes no text, then steps back to the last questions.
+    script.extend(events::typed("x"));
+    assert_eq!(input_text(&harness.run(script.clone())), "");
+    script.extend([key(KeyCode::BackTab), key(KeyCode::BackTab), key(KeyCode::BackTab), key(KeyCode::BackTab)]);
+    let screen = harness.run(script.clone());
+    assert!(!row(&screen, 1).starts_with("┌Review"));
+    assert_eq!(input_text(&screen), "ls");
+
+    script.extend(answers(&["pwd", "", "powershell", "powershell"]));
+    script.push(enter());
+    let screen = harness.run(script);
+    assert!(screen.contains("Preset created successfuly :)"));
+
+    let config = harness.config();
+    let created = config.get_preset_by_name("Work").expect("The preset was not written.");
+    assert_eq!(created.get_args(), ["pwd"]);
+}
+
+#[test]
+fn create_wizard_offers_profile_and_shell_choices() {
+    let harness = Harness::new("wizard_choices", vec![]);
+    harness.set_setting(
+        "preset_defaults",
+        serde_json::json!({ "wt_profile": "Ubuntu", "init_shell": "powershell", "target_shell": "bash" })
+    );
+    let mut script = vec![key(KeyCode::Down), enter()];
+
+    // Every question starts from the default in Settings.
+    script.extend(answers(&["Work", "1", "1", "ls"]));
+    let screen = harness.run(script.clone());
+    assert_eq!(input_text(&screen), "Ubuntu");
+    script.push(enter());
+    let screen = harness.run(script.clone());
+    assert_eq!(input_text(&screen), "powershell");
+
+    script.push(key(KeyCode::Down));
+    assert_eq!(input_text(&harness.run(script.clone())), "pwsh");
+    script.extend([key(KeyCode::Up), key(KeyCode::Up)]);
+    assert_eq!(input_text(&harness.run(script.clone())), "cmd");
+    script.extend(answers(&["nu"]));
+    assert!(harness.run(script.clone()).contains("│Choose one of: powershell, pwsh, cmd."));
+    // wt.exe is started from Windows shells only.
+    script.extend(answers(&["bash"]));
+    let screen = harness.run(script.clone());
+    assert!(screen.contains("│Choose one of: powershell, pwsh, cmd."));
+    assert_eq!(input_text(&screen), "bash");
+    script.extend(answers(&["cmd"]));
+    assert_eq!(input_text(&harness.run(script.clone())), "bash");
+
+    // The target shell can be any of them.
+    script.push(key(KeyCode::Down));
+    assert_eq!(input_text(&harness.run(script.clone())), "zsh");
+    script.push(key(KeyCode::Up));
+
+    script.push(enter());
+    let review = harness.run(script.clone());
+    assert!(review.contains("│Profile: Ubuntu"));
+    assert!(review.contains("│Shells: cmd -> bash"));
+
+    script.push(enter());
+    harness.run(script);
+    let config = harness.config();
+    let created = config.get_preset_by_name("Work").expect("The preset was not written.");
+    assert_eq!(created.get_preset_info(), PresetInfo::new(String::from("Ubuntu"), ShellType::Cmd, ShellType::Bash));
+}
+
+#[test]
+fn settings_toggles_flip_and_choices_are_picked() {
+    let harness = Harness::new("settings", vec![]);
+    let settings = vec![key(KeyCode::Down), key(KeyCode::Down), enter()];
+
+    // Space on a toggle flips it and saves at once, debug mode shows the state.
+    let mut script = settings.clone();
+    script.push(key(KeyCode::Char(' ')));
+    let screen = harness.run(script);
+    assert!(screen.contains("│> Debug mode: true"));
+    assert!(row(&screen, 0).contains("State: Settings, InputMode: Normal"));
+    assert_eq!(harness.setting("debug_mode"), true);
+
+    let mut script = settings.clone();
+    script.extend([key(KeyCode::Down), enter()]);
+    let screen = harness.run(script);
+    assert!(row(&screen, 0).contains("InputMode: Edit"));
+    assert_eq!(input_text(&screen), "30");
+
+    let mut script = settings;
+    script.extend([key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Down), enter()]);
+    let screen = harness.run(script.clone());
+    assert!(screen.contains("│ │> powershell"));
+    assert_eq!(input_text(&screen), "< powershell >");
+    script.extend([key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Up), enter()]);
+    let screen = harness.run(script);
+    assert!(row(&screen, 0).contains("State: Settings, InputMode: Normal"));
+    assert!(screen.contains("Default init shell: pwsh"));
+    assert_eq!(harness.setting("preset_defaults")["init_shell"], "pwsh");
+}
+
+#[test]
+fn launching_asks_first_when_configured() {
+    let harness = Harness::new("launch_confirm", vec![preset("Home")]);
+    harness.set_setting("confirm_before_launch", serde_json::Value::Bool(true));
+
+    let question = harness.run(vec![enter(), enter()]);
+    assert!(question.contains("Launch 'Home'? (y/n)"));
+
+    let screen = harness.run(vec![enter(), enter(), key(KeyCode::Char('n'))]);
+    assert!(!screen.contains("Launch 'Home'?"));
+    assert!(screen.contains("│> Home"));
+    assert!(harness.launched().is_empty());
+
+    harness.run(vec![enter(), enter(), key(KeyCode::Char('n')), enter(), key(KeyCode::Char('y'))]);
+    let expected = harness.config().create_launch_plan("Home").unwrap();
+    assert_eq!(harness.launched(), [expected]);
+}
+
+#[test]
+fn long_lists_scroll_with_the_selection() {
+    let presets = (0..30).map(|n| preset(&format!("Preset {:02}", n))).collect();
+    let harness = Harness::new("scroll", presets);
+    let mut script = vec![enter()];
+    script.extend((0..20).map(|_| key(KeyCode::Down)));
+
+    let screen = harness.run(script.clone());
+    assert!(row(&screen, 2).starts_with("│  Preset 03"));
+    assert!(row(&screen, 19).starts_with("│> Preset 20"));
+
+    // Rows map to the items scrolled into view.
+    script.push(click(5, 5));
+    assert!(harness.run(script.clone()).contains("│> Preset 06"));
+
+    script.extend((0..5).map(|_| key(KeyCode::Up)));
+    let screen = harness.run(script);
+    assert!(row(&screen, 2).starts_with("│> Preset 01"));
+}
+
+#[test]
+fn mouse_click_selects_and_double_click_chooses() {
+    let harness = Harness::new("mouse", vec![]);
+
+    let screen = harness.run(vec![click(5, 3)]);
+    assert!(screen.contains("│> Create Preset"));
+    assert!(!screen.contains("Enter preset name:"));
+
+    // Rows below the last item select nothing.
+    let screen = harness.run(vec![click(5, 3), click(5, 10)]);
+    assert!(screen.contains("│> Create Preset"));
+
+    let screen = harness.run(vec![click(5, 3), click(5, 3)]);
+    assert!(screen.contains("│Enter preset name:"));
+
+    // A click outside a message closes it.
+    let failed = AppEvent::Message(AppMessage::Launched {
+        name: String::from("Home"),
+        result: Err(String::from("wt.exe was not found.")),
+    });
+    assert!(harness.run(vec![failed.clone()]).contains("wt.exe was not found."));
+    assert!(!harness.run(vec![failed, click(5, 20)]).contains("wt.exe was not found."));
+}
+
+#[test]
+fn config_changes_outside_the_app_are_reloaded() {
+    let harness = Harness::new("reload", vec![preset("Home")]);
+    let changed = Step::Event(AppEvent::Message(AppMessage::ConfigChanged));
+
+    // The app's own save is already loaded.
+    let screen = harness.run_steps(vec![Step::Event(enter()), changed]);
+    assert!(!screen.contains("reloaded"));
+
+    let config_path = harness.config_path.to_string_lossy().to_string();
+    let change = Step::Do(
+        Box::new(move || {
+            let mut config = AppConfig::load(&config_path).unwrap();
+            config.add_presets(vec![preset("Second")]);
+            config.write_preset_to_file(&[], WriteType::Edit, &config_path).unwrap();
+        })
+    );
+    let changed = Step::Event(AppEvent::Message(AppMessage::ConfigChanged));
+    let screen = harness.run_steps(vec![Step::Event(enter()), change, changed]);
+    assert!(screen.contains("│> Home"));
+    assert!(screen.contains("│  Second"));
+    assert!(screen.contains("│The config was changed outside the app and"));
+
+    let launched = AppMessage::Launched { name: String::from("Second"), result: Ok(()) };
+    let screen = harness.run(vec![AppEvent::Message(launched)]);
+    assert!(screen.contains("Launched 'Second'."));
+}
+
+const REVIEW_SNAPSHOT: &str = "\
+Press ESC to go back, SHIFT+TAB for the previous question, F1 for help
+┌Review────────────────────────────────────────────────────────────────────────┐
+│Name: Work                                                                    │
+│Tabs: 1                                                                       │
+│Profile: default                                                              │
+│Shells: powershell -> powershell                                              │
+│Tab #1: 2 windows                                                             │
+│  1. ls                                                                       │
+│  2. cargo test                                                               │
+│                                                                              │
+│Press ENTER to create the preset, SHIFT+TAB to change the last answer or ESC  │
+│to cancel.                                                                    │
+│                                                                              │
+│                                                                              │
+│                                                                              │
+│                                                                              │
+│                                                                              │
+│                                                                              │
+│                                                                              │
+│                                                                              │
+└──────────────────────────────────────────────────────────────────────────────┘
+┌Input─────────────────────────────────────────────────────────────────────────┐
+│                                                                              │
+└──────────────────────────────────────────────────────────────────────────────┘";
+
+const DELETE_SNAPSHOT: &str = "\
+Press ESC to go back, q to exit, e to edit, DEL to delete, / to search, ? for he
+┌──────────────────────────────┐┌Details───────────────────────────────────────┐
+│> Home                        ││Tabs: 1, windows: 1                           │
+│                              ││Profile: default                              │
+│                              ││Shells: powershell -> powershell              │
+│                              ││                                              │
+│                              ││Tab #1                                        │
+│                              ││┌1───────────────────────────────────────────┐│
+│                              │││ls                                          ││
+│               ┌──────────────────────────────────────────────┐              ││
+│               │Move 'Home' to the trash? It is deleted for   │              ││
+│               │good after 30 days. (y/n)                     │              ││
+│               └──────────────────────────────────────────────┘              ││
+│                              │││                                            ││
+│                              │││                                            ││
+│                              │││                                            ││
+│                              │││                                            ││
+│                              │││                                            ││
+│                              │││                                            ││
+│                              ││└────────────────────────────────────────────┘│
+└──────────────────────────────┘└──────────────────────────────────────────────┘
+┌Input─────────────────────────────────────────────────────────────────────────┐
+│                                                                              │
+└──────────────────────────────────────────────────────────────────────────────┘";
+